@@ -1,32 +1,144 @@
 use crate::{
+    brush::{Brush, BrushCursor},
     buffer::Buffer,
-    canvas::{Canvas, CANVAS_HEIGHT, CANVAS_WIDTH},
+    canvas::{Canvas, PngError},
+    color::Color,
+    color_editor::{self, ColorEditor},
     color_picker::{self, ColorPicker},
-    tools::{Rectangel, Tool},
+    dither::Dithering,
+    document::{self, Document, LayerChange},
+    grid::Grid,
+    history::History,
+    pattern::Pattern,
+    region::{Connectivity, Distance, RegionOptions},
+    scale::Scaler,
+    selection::{Ants, Clip, Selection},
+    tools::{FillMode, Modifiers, Pipette, Rectangel, Tool},
+    transform::Transform,
+    viewport::Viewport,
     widget::Widget,
     BORDER_WIDTH,
 };
 
+use winit_input_helper::TextChar;
+
+use std::{mem, path::Path, time::Instant};
+
+const ANTS_PERIOD_MS: u128 = 150;
+
+/// Left mouse button paints with the primary color, right with the
+/// secondary one.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Button {
+    Primary,
+    Secondary,
+}
+
 pub struct App {
     canvas: Canvas,
+    viewport: Viewport,
     tool: Box<dyn Tool>,
+    suspended_tool: Option<Box<dyn Tool>>,
+    color: Color,
+    secondary_color: Color,
+    fill_color: Color,
+    fill_mode: FillMode,
+    pattern: Pattern,
+    dithering: Dithering,
+    brush: Brush,
+    region_options: RegionOptions,
+    mouse: (isize, isize),
+    modifiers: Modifiers,
+    pressed: bool,
+    button: Button,
     color_picker: ColorPicker,
+    color_editor: ColorEditor,
+    grid: Grid,
+    clipboard: Option<Clip>,
+    history: History,
+    started: Instant,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
+        let canvas = Canvas::new(width, height);
+        let viewport = Viewport::new(
+            (BORDER_WIDTH as usize, BORDER_WIDTH as usize),
+            (width, height),
+        );
+        let mut color_picker = ColorPicker::new(Self::picker_top(&viewport));
+        color_picker.set_swatches(Color::black(), Color::white());
+        let color_editor = ColorEditor::new(Self::editor_top(&viewport));
+
         Self {
-            canvas: Canvas::new(),
-            tool: box Rectangel::new(),
-            color_picker: ColorPicker::new(),
+            canvas,
+            viewport,
+            tool: Box::new(Rectangel::new()),
+            suspended_tool: None,
+            color: Color::black(),
+            secondary_color: Color::white(),
+            fill_color: Color::white(),
+            fill_mode: FillMode::Outline,
+            pattern: Pattern::default(),
+            dithering: Dithering::Bayer,
+            brush: Brush::default(),
+            region_options: RegionOptions::default(),
+            mouse: (0, 0),
+            modifiers: Modifiers::default(),
+            pressed: false,
+            button: Button::Primary,
+            color_picker,
+            color_editor,
+            grid: Grid::default(),
+            clipboard: None,
+            history: History::new(),
+            started: Instant::now(),
         }
     }
 
-    pub fn handle_press(&mut self, mouse: (isize, isize)) {
-        self.tool.handle_press(mouse, &mut self.canvas);
-        if let Some(color) = self.color_picker.pick_color(mouse) {
-            self.tool.set_outline_color(color);
+    pub fn frame_size(&self) -> (u32, u32) {
+        let (view_width, view_height) = self.viewport.size();
+        let width = (view_width as u32)
+            .max(self.color_picker.width())
+            .max(color_editor::EDITOR_WIDTH)
+            + 2 * BORDER_WIDTH;
+        let height = view_height as u32
+            + 4 * BORDER_WIDTH
+            + color_picker::BUTTON_SIZE
+            + color_editor::EDITOR_HEIGHT;
+
+        (width, height)
+    }
+
+    pub fn handle_press(&mut self, mouse: (isize, isize), button: Button) {
+        // The editor shows the primary color, so only the left button
+        // edits it.
+        let edited = match button {
+            Button::Primary => self.color_editor.press(mouse),
+            Button::Secondary => None,
+        };
+
+        if self.in_view(mouse) {
+            self.end_stroke();
+            self.canvas.begin_stroke();
+            self.pressed = true;
+            self.button = button;
+            self.sync_colors();
+
+            let mouse = self.viewport.frame_to_canvas(mouse);
+            self.tool.handle_press(mouse, &mut self.canvas);
+        } else if let Some(color) = self.color_picker.pick_color(mouse) {
+            if self.modifiers.shift {
+                self.set_fill_color(color);
+            } else if button == Button::Secondary {
+                self.set_secondary_color(color);
+            } else {
+                self.set_color(color);
+            }
+        } else if let Some(color) = edited {
+            self.set_color(color);
         }
+        self.take_picked_color();
     }
 
     pub fn handle_hold(
@@ -34,41 +146,716 @@ impl App {
         prev_mouse: (isize, isize),
         curr_mouse: (isize, isize),
     ) {
+        if let Some(color) = self.color_editor.drag(curr_mouse) {
+            self.set_color(color);
+        }
+        if !self.pressed {
+            return;
+        }
+
+        let prev_mouse = self.viewport.frame_to_canvas(prev_mouse);
+        let curr_mouse = self.viewport.frame_to_canvas(curr_mouse);
         self.tool
             .handle_hold(prev_mouse, curr_mouse, &mut self.canvas);
+        self.take_picked_color();
     }
 
     pub fn handle_release(&mut self, mouse: (isize, isize)) {
+        self.color_editor.release();
+        if !self.pressed {
+            return;
+        }
+
+        let mouse = self.viewport.frame_to_canvas(mouse);
         self.tool.handle_release(mouse, &mut self.canvas);
+        self.take_picked_color();
+        self.end_stroke();
+        self.pressed = false;
+        self.sync_colors();
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+        self.sync_colors();
+    }
+
+    pub fn is_typing(&self) -> bool {
+        self.color_editor.is_typing()
+    }
+
+    pub fn type_text(&mut self, text: &[TextChar]) {
+        if let Some(color) = self.color_editor.type_text(text) {
+            self.set_color(color);
+        }
+    }
+
+    pub fn set_secondary_color(&mut self, color: Color) {
+        self.secondary_color = color;
+        self.sync_colors();
+    }
+
+    pub fn swap_colors(&mut self) {
+        mem::swap(&mut self.color, &mut self.secondary_color);
+        self.sync_colors();
+    }
+
+    pub fn set_fill_color(&mut self, color: Color) {
+        self.fill_color = color;
+        self.for_each_tool(|tool| tool.set_fill_color(color));
+    }
+
+    pub fn cycle_fill_mode(&mut self) {
+        let fill_mode = self.fill_mode.next();
+        self.fill_mode = fill_mode;
+        self.for_each_tool(|tool| tool.set_fill_mode(fill_mode));
+    }
+
+    pub fn cycle_pattern(&mut self) {
+        self.set_pattern(self.pattern.next());
+    }
+
+    pub fn change_pattern_level(&mut self, delta: isize) {
+        self.set_pattern(self.pattern.with_level_offset(delta));
+    }
+
+    /// Takes a custom pattern from the floating or selected pixels.
+    pub fn pattern_from_selection(&mut self) {
+        let clip = match self.canvas.floating() {
+            Some(clip) => Some(clip.clone()),
+            None => self.canvas.copy_selection(),
+        };
+
+        if let Some(clip) = clip {
+            self.set_pattern(Pattern::from_clip(&clip));
+        }
+    }
+
+    pub fn cycle_dithering(&mut self) {
+        self.set_dithering(self.dithering.next());
+    }
+
+    pub fn handle_move(&mut self, mouse: (isize, isize)) {
+        self.mouse = self.viewport.frame_to_canvas(mouse);
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    pub fn pan(&mut self, delta: (isize, isize)) {
+        self.viewport.pan(delta);
+    }
+
+    pub fn zoom_in(&mut self, anchor: (isize, isize)) {
+        self.viewport.zoom_in(anchor);
+    }
+
+    pub fn zoom_out(&mut self, anchor: (isize, isize)) {
+        self.viewport.zoom_out(anchor);
+    }
+
+    pub fn zoom(&self) -> usize {
+        self.viewport.zoom()
+    }
+
+    pub fn toggle_pixel_grid(&mut self) {
+        self.grid.pixel_grid = !self.grid.pixel_grid;
+    }
+
+    pub fn toggle_tile_grid(&mut self) {
+        self.grid.tile_grid = !self.grid.tile_grid;
+    }
+
+    pub fn set_tile_size(&mut self, tile_size: (usize, usize)) {
+        self.grid.tile_size = tile_size;
+    }
+
+    pub fn set_tile_color(&mut self, color: Color) {
+        self.grid.tile_color = color;
+    }
+
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+        self.set_picking(modifiers.alt);
+        self.for_each_tool(|tool| tool.set_modifiers(modifiers));
+    }
+
+    pub fn change_brush_size(&mut self, delta: isize) {
+        let size = (self.brush.size() as isize + delta).max(1) as usize;
+        self.set_brush(self.brush.with_size(size));
+    }
+
+    pub fn cycle_brush_shape(&mut self) {
+        self.set_brush(self.brush.with_next_shape());
+    }
+
+    pub fn copy(&mut self) {
+        let clip = match self.canvas.floating() {
+            Some(clip) => Some(clip.clone()),
+            None => self.canvas.copy_selection(),
+        };
+
+        if clip.is_some() {
+            self.clipboard = clip;
+        }
+    }
+
+    pub fn cut(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.copy();
+        if self.canvas.discard_floating().is_none() {
+            self.end_stroke();
+            self.canvas.begin_stroke();
+            self.canvas.clear_selection();
+            self.end_stroke();
+        }
+    }
+
+    pub fn paste(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        if let Some(clip) = self.clipboard.clone() {
+            self.commit_floating();
+            self.canvas.paste(clip);
+        }
+    }
+
+    pub fn commit_floating(&mut self) {
+        if self.pressed || self.canvas.floating().is_none() {
+            return;
+        }
+
+        self.end_stroke();
+        self.canvas.begin_stroke();
+        self.canvas.commit_floating();
+        self.end_stroke();
+    }
+
+    pub fn deselect(&mut self) {
+        self.edit_selection(|_, _| None);
+    }
+
+    pub fn select_all(&mut self) {
+        self.edit_selection(|_, size| Some(Selection::all(size)));
+    }
+
+    pub fn invert_selection(&mut self) {
+        self.edit_selection(|selection, size| match selection {
+            Some(selection) => Some(selection.invert(size)),
+            None => Some(Selection::all(size)),
+        });
+    }
+
+    pub fn grow_selection(&mut self, n: usize) {
+        self.edit_selection(|selection, _| selection.map(|s| s.grow(n)));
+    }
+
+    pub fn shrink_selection(&mut self, n: usize) {
+        self.edit_selection(|selection, _| selection.map(|s| s.shrink(n)));
+    }
+
+    pub fn change_tolerance(&mut self, delta: i16) {
+        let tolerance = self.region_options.tolerance as i16 + delta;
+        self.region_options.tolerance = tolerance.clamp(0, 0xff) as u8;
+        self.set_region_options(self.region_options);
+    }
+
+    pub fn toggle_connectivity(&mut self) {
+        let connectivity = self.region_options.connectivity.toggle();
+        self.region_options.connectivity = connectivity;
+        self.set_region_options(self.region_options);
+    }
+
+    pub fn toggle_contiguous(&mut self) {
+        self.region_options.contiguous = !self.region_options.contiguous;
+        self.set_region_options(self.region_options);
+    }
+
+    pub fn toggle_distance(&mut self) {
+        self.region_options.distance = self.region_options.distance.toggle();
+        self.set_region_options(self.region_options);
+    }
+
+    pub fn undo(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        if let Some(cleared) = self.canvas.discard_floating() {
+            // Pasted pixels live only in the floating clip, so dropping it is
+            // the whole undo. Lifted ones still have a stroke to revert,
+            // unless the lift found nothing to clear and recorded none.
+            if !cleared {
+                return;
+            }
+        }
+
+        if let Some(step) = self.history.undo() {
+            self.canvas.undo_step(step);
+        }
+        self.update_layout();
+    }
+
+    pub fn redo(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.commit_floating();
+        if let Some(step) = self.history.redo() {
+            self.canvas.redo_step(step);
+        }
+        self.update_layout();
+    }
+
+    pub fn resize_canvas(&mut self, width: usize, height: usize) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_document(|document| document.resize(width, height));
+    }
+
+    /// Transforms the floating or selected pixels, or the whole document
+    /// when nothing is selected.
+    pub fn transform(&mut self, transform: Transform) {
+        if self.pressed {
+            return;
+        }
+
+        if self.canvas.selection().is_some() {
+            self.map_selection(|clip| clip.transform(transform));
+        } else {
+            self.edit_document(|document| document.transform(transform));
+        }
+    }
+
+    /// Scales the floating or selected pixels from their top left corner,
+    /// or the whole document when nothing is selected.
+    pub fn scale(&mut self, scaler: Scaler) {
+        if self.pressed {
+            return;
+        }
+
+        let size = self
+            .canvas
+            .selection()
+            .map_or(self.canvas_size(), Selection::size);
+        if !document::is_valid_size(scaler.size(size)) {
+            return;
+        }
+
+        if self.canvas.selection().is_some() {
+            self.map_selection(|clip| clip.scale(scaler));
+        } else {
+            self.edit_document(|document| document.scale(scaler));
+        }
+    }
+
+    pub fn add_layer(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::add_layer);
+    }
+
+    pub fn remove_layer(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::remove_layer);
+    }
+
+    pub fn duplicate_layer(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::duplicate_layer);
+    }
+
+    pub fn merge_layer_down(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::merge_down);
+    }
+
+    pub fn raise_layer(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::raise_layer);
+    }
+
+    pub fn lower_layer(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::lower_layer);
+    }
+
+    pub fn select_layer(&mut self, offset: isize) {
+        if self.pressed {
+            return;
+        }
+
+        self.commit_floating();
+        self.end_stroke();
+
+        let document = self.canvas.document_mut();
+        let ix = (document.active() as isize + offset).max(0);
+        document.select(ix as usize);
+    }
+
+    pub fn toggle_layer_visibility(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::toggle_visibility);
+    }
+
+    pub fn toggle_layer_lock(&mut self) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(Document::toggle_lock);
+    }
+
+    pub fn change_layer_opacity(&mut self, delta: i16) {
+        if self.pressed {
+            return;
+        }
+
+        self.edit_layers(|document| document.change_opacity(delta));
+    }
+
+    pub fn layer_status(&self) -> String {
+        let document = self.canvas.document();
+        let layer = document.active_layer();
+
+        format!(
+            "{} ({}/{}, {}%{}{})",
+            layer.name,
+            document.active() + 1,
+            document.layers().len(),
+            layer.opacity as u32 * 100 / 0xff,
+            if layer.visible { "" } else { ", hidden" },
+            if layer.locked { ", locked" } else { "" },
+        )
+    }
+
+    pub fn fill_status(&self) -> String {
+        let fill_mode = match self.fill_mode {
+            FillMode::Outline => "outline",
+            FillMode::Fill => "fill",
+            FillMode::OutlineAndFill => "outline + fill",
+        };
+        let dithering = match self.dithering {
+            Dithering::Off => "no dithering",
+            Dithering::Nearest => "nearest color",
+            Dithering::Bayer => "bayer dithering",
+        };
+        let pattern = match self.pattern {
+            Pattern::Solid => "solid".to_string(),
+            Pattern::Bayer { size, level } => {
+                format!("bayer {}x{} {}/{}", size, size, level, size * size)
+            }
+            Pattern::Custom { width, height, .. } => {
+                format!("custom {}x{}", width, height)
+            }
+        };
+
+        format!(
+            "{} ({}), fill {}, {}",
+            fill_mode,
+            pattern,
+            self.fill_color.to_hex(),
+            dithering
+        )
+    }
+
+    pub fn region_status(&self) -> String {
+        let RegionOptions {
+            tolerance,
+            distance,
+            connectivity,
+            contiguous,
+        } = self.region_options;
+        let distance = match distance {
+            Distance::Channel => "channel",
+            Distance::Perceptual => "perceptual",
+        };
+        let connectivity = match connectivity {
+            Connectivity::Four => 4,
+            Connectivity::Eight => 8,
+        };
+
+        format!(
+            "tolerance {} ({}), {}-connected{}",
+            tolerance,
+            distance,
+            connectivity,
+            if contiguous { "" } else { ", global" },
+        )
+    }
+
+    pub fn canvas_size(&self) -> (usize, usize) {
+        (self.canvas.width(), self.canvas.height())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PngError> {
+        self.canvas.to_png(path)
+    }
+
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), PngError> {
+        self.canvas = Canvas::from_png(path)?;
+        self.history.clear();
+        self.clipboard = None;
+        self.update_layout();
+
+        Ok(())
     }
 
     pub fn switch_tool(&mut self, tool: Box<dyn Tool>) {
+        if self.pressed {
+            return;
+        }
+
+        self.commit_floating();
         self.tool = tool;
+        let (color, secondary_color) = self.stroke_colors();
+        self.tool.set_outline_color(color);
+        self.tool.set_secondary_color(secondary_color);
+        self.tool.set_fill_color(self.fill_color);
+        self.tool.set_fill_mode(self.fill_mode);
+        self.tool.set_pattern(self.pattern.clone());
+        self.tool.set_brush(self.brush.clone());
+        self.tool.set_modifiers(self.modifiers);
+        self.tool.set_region_options(self.region_options);
+        self.tool
+            .set_dithering(self.dithering, &self.color_picker.colors());
+        self.suspended_tool = None;
     }
 
     pub fn draw(&self, frame: &mut [u8]) {
-        let mut buffer = Buffer::new(frame);
+        let (frame_width, frame_height) = self.frame_size();
+        let mut buffer =
+            Buffer::new(frame, frame_width as usize, frame_height as usize);
+        buffer.clear(Color::black());
 
         {
-            let mut canvas_buffer = buffer.lend(box {
-                let (x, y) = (BORDER_WIDTH, BORDER_WIDTH);
-                let (width, height) = (CANVAS_WIDTH, CANVAS_HEIGHT);
-
-                ((x as usize, y as usize), (width as usize, height as usize))
-            });
+            let mut canvas_buffer = buffer.lend_viewport(self.viewport);
 
             self.canvas.display(&mut canvas_buffer);
+            self.grid.display(&mut canvas_buffer);
+
+            if let Some(selection) = self.canvas.selection() {
+                let elapsed = self.started.elapsed().as_millis();
+                let ants = Ants {
+                    selection,
+                    phase: (elapsed / ANTS_PERIOD_MS) as usize,
+                };
+                ants.display(&mut canvas_buffer);
+            }
+
             self.tool.display(&mut canvas_buffer);
+
+            if self.tool.uses_brush() {
+                let cursor = BrushCursor {
+                    brush: &self.brush,
+                    position: self.mouse,
+                    color: self.color.fade(0x80),
+                };
+                cursor.display(&mut canvas_buffer);
+            }
         }
 
-        let mut picker_buffer = buffer.lend(box {
-            let x = BORDER_WIDTH;
-            let y = 2 * BORDER_WIDTH + CANVAS_HEIGHT;
-            let width = CANVAS_WIDTH;
-            let height = color_picker::BUTTON_SIZE;
+        let mut picker_buffer = buffer.lend(Box::new({
+            let x = BORDER_WIDTH as usize;
+            let y = Self::picker_top(&self.viewport) as usize;
+            let width = frame_width as usize - 2 * BORDER_WIDTH as usize;
+            let height = color_picker::BUTTON_SIZE as usize;
 
-            ((x as usize, y as usize), (width as usize, height as usize))
-        });
+            ((x, y), (width, height))
+        }));
         self.color_picker.display(&mut picker_buffer);
+
+        let mut editor_buffer = buffer.lend(Box::new({
+            let x = BORDER_WIDTH as usize;
+            let y = Self::editor_top(&self.viewport) as usize;
+            let width = frame_width as usize - 2 * BORDER_WIDTH as usize;
+            let height = color_editor::EDITOR_HEIGHT as usize;
+
+            ((x, y), (width, height))
+        }));
+        self.color_editor.display(&mut editor_buffer);
+    }
+
+    fn edit_document<F>(&mut self, edit: F)
+    where
+        F: FnOnce(&mut Document) -> bool,
+    {
+        self.commit_floating();
+        self.end_stroke();
+
+        if let Some(step) = self.canvas.edit_document(edit) {
+            self.history.push(step);
+        }
+        self.update_layout();
+    }
+
+    fn edit_layers<F>(&mut self, edit: F)
+    where
+        F: FnOnce(&mut Document) -> Option<LayerChange>,
+    {
+        self.commit_floating();
+        self.end_stroke();
+
+        if let Some(step) = self.canvas.edit_layers(edit) {
+            self.history.push(step);
+        }
+    }
+
+    fn map_selection<F>(&mut self, f: F)
+    where
+        F: FnOnce(&Clip) -> Clip,
+    {
+        if self.canvas.floating().is_some() {
+            self.canvas.map_floating(f);
+        } else {
+            self.end_stroke();
+            self.canvas.begin_stroke();
+            self.canvas.map_selection(f);
+            self.end_stroke();
+        }
+    }
+
+    fn edit_selection<F>(&mut self, edit: F)
+    where
+        F: FnOnce(Option<&Selection>, (usize, usize)) -> Option<Selection>,
+    {
+        if self.pressed {
+            return;
+        }
+
+        self.commit_floating();
+        let selection = edit(self.canvas.selection(), self.canvas_size());
+        self.canvas
+            .set_selection(selection.filter(|selection| !selection.is_empty()));
+    }
+
+    fn set_picking(&mut self, picking: bool) {
+        if self.pressed || picking == self.suspended_tool.is_some() {
+            return;
+        }
+        if picking && self.tool.uses_alt() {
+            return;
+        }
+
+        if picking {
+            let tool = mem::replace(&mut self.tool, Box::new(Pipette::new()));
+            self.suspended_tool = Some(tool);
+        } else if let Some(tool) = self.suspended_tool.take() {
+            self.tool = tool;
+        }
+    }
+
+    fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush.clone();
+        self.for_each_tool(|tool| tool.set_brush(brush.clone()));
+    }
+
+    fn set_region_options(&mut self, options: RegionOptions) {
+        self.for_each_tool(|tool| tool.set_region_options(options));
+    }
+
+    fn set_pattern(&mut self, pattern: Pattern) {
+        self.pattern = pattern.clone();
+        self.for_each_tool(|tool| tool.set_pattern(pattern.clone()));
+    }
+
+    fn set_dithering(&mut self, dithering: Dithering) {
+        self.dithering = dithering;
+
+        let palette = self.color_picker.colors();
+        self.for_each_tool(|tool| tool.set_dithering(dithering, &palette));
+    }
+
+    fn for_each_tool<F>(&mut self, f: F)
+    where
+        F: Fn(&mut dyn Tool),
+    {
+        f(self.tool.as_mut());
+        if let Some(tool) = &mut self.suspended_tool {
+            f(tool.as_mut());
+        }
+    }
+
+    fn take_picked_color(&mut self) {
+        if let Some(color) = self.tool.take_picked_color() {
+            match self.button {
+                Button::Primary => self.set_color(color),
+                Button::Secondary => self.set_secondary_color(color),
+            }
+        }
+    }
+
+    /// Colors the tools paint with and blend towards: swapped while a stroke
+    /// started with the right button is in progress.
+    fn stroke_colors(&self) -> (Color, Color) {
+        match self.button {
+            Button::Secondary if self.pressed => {
+                (self.secondary_color, self.color)
+            }
+            _ => (self.color, self.secondary_color),
+        }
+    }
+
+    fn sync_colors(&mut self) {
+        let (color, secondary_color) = self.stroke_colors();
+        self.for_each_tool(|tool| {
+            tool.set_outline_color(color);
+            tool.set_secondary_color(secondary_color);
+        });
+        self.color_picker.set_swatches(self.color, self.secondary_color);
+        self.color_editor.set_color(self.color);
+    }
+
+    fn end_stroke(&mut self) {
+        if let Some(step) = self.canvas.end_stroke() {
+            self.history.push(step);
+        }
+    }
+
+    fn update_layout(&mut self) {
+        self.viewport.set_canvas_size(self.canvas_size());
+        self.color_picker.set_top(Self::picker_top(&self.viewport));
+        self.color_editor.set_top(Self::editor_top(&self.viewport));
+    }
+
+    fn in_view(&self, (x, y): (isize, isize)) -> bool {
+        x >= 0 && y >= 0 && self.viewport.contains(x as usize, y as usize)
+    }
+
+    fn picker_top(viewport: &Viewport) -> isize {
+        (viewport.size().1 + 2 * BORDER_WIDTH as usize) as isize
+    }
+
+    fn editor_top(viewport: &Viewport) -> isize {
+        Self::picker_top(viewport)
+            + (color_picker::BUTTON_SIZE + BORDER_WIDTH) as isize
     }
 }
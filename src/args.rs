@@ -0,0 +1,79 @@
+use crate::{color::Color, document};
+
+use std::{env, ffi::OsString, path::PathBuf};
+
+const DEFAULT_PATH: &str = "canvas.png";
+const USAGE: &str = "usage: pant [--size WIDTHxHEIGHT] [--tile WIDTHxHEIGHT] \
+                     [--tile-color RRGGBB[AA]] [FILE]";
+
+pub struct Args {
+    pub path: PathBuf,
+    pub size: Option<(usize, usize)>,
+    pub tile_size: Option<(usize, usize)>,
+    pub tile_color: Option<Color>,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self, String> {
+        let mut path = None;
+        let mut size = None;
+        let mut tile_size = None;
+        let mut tile_color = None;
+        let mut args = env::args_os().skip(1);
+
+        while let Some(arg) = args.next() {
+            if arg == "--size" || arg == "-s" {
+                size = Some(parse_size(&next_value(&mut args)?)?);
+            } else if arg == "--tile" || arg == "-t" {
+                tile_size = Some(parse_size(&next_value(&mut args)?)?);
+            } else if arg == "--tile-color" {
+                let value = next_value(&mut args)?;
+                let color = Color::from_hex(&value).ok_or_else(|| {
+                    format!("invalid tile color `{}`\n{}", value, USAGE)
+                })?;
+                tile_color = Some(color);
+            } else if path.is_none() {
+                path = Some(PathBuf::from(arg));
+            } else {
+                return Err(USAGE.to_string());
+            }
+        }
+
+        Ok(Self {
+            path: path.unwrap_or_else(|| PathBuf::from(DEFAULT_PATH)),
+            size,
+            tile_size,
+            tile_color,
+        })
+    }
+}
+
+fn next_value<I>(args: &mut I) -> Result<String, String>
+where
+    I: Iterator<Item = OsString>,
+{
+    args.next()
+        .and_then(|value| value.into_string().ok())
+        .ok_or_else(|| USAGE.to_string())
+}
+
+fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("invalid size `{}`\n{}", value, USAGE);
+    let (width, height) = value.split_once('x').ok_or_else(invalid)?;
+    let width = width.parse::<usize>().map_err(|_| invalid())?;
+    let height = height.parse::<usize>().map_err(|_| invalid())?;
+
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    if !document::is_valid_size((width, height)) {
+        return Err(format!(
+            "size `{}` is larger than {}x{}",
+            value,
+            document::MAX_SIZE,
+            document::MAX_SIZE
+        ));
+    }
+
+    Ok((width, height))
+}
@@ -0,0 +1,128 @@
+use crate::{buffer::GuardedBuffer, color::Color, widget::Widget};
+
+pub const MAX_BRUSH_SIZE: usize = 32;
+
+#[derive(Clone)]
+pub enum Shape {
+    Square,
+    Round,
+    Bitmap {
+        width: usize,
+        height: usize,
+        mask: Vec<bool>,
+    },
+}
+
+impl Shape {
+    pub fn nib() -> Self {
+        let mask = "..#\
+                    .#.\
+                    #.."
+            .chars()
+            .map(|c| c == '#')
+            .collect();
+
+        Self::Bitmap {
+            width: 3,
+            height: 3,
+            mask,
+        }
+    }
+
+    fn contains(&self, i: usize, j: usize, size: usize) -> bool {
+        match self {
+            Self::Square => true,
+            Self::Round => {
+                let c_x = (2 * i + 1) as isize - size as isize;
+                let c_y = (2 * j + 1) as isize - size as isize;
+                let size = size as isize;
+
+                c_x * c_x + c_y * c_y <= size * size - size
+            }
+            Self::Bitmap {
+                width,
+                height,
+                mask,
+            } => mask[i * width / size + j * height / size * width],
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Brush {
+    shape: Shape,
+    size: usize,
+    offsets: Vec<(isize, isize)>,
+}
+
+impl Brush {
+    pub fn new(shape: Shape, size: usize) -> Self {
+        let size = size.clamp(1, MAX_BRUSH_SIZE);
+        let start = -(size as isize - 1) / 2;
+        let mut offsets = (0..size)
+            .flat_map(|j| (0..size).map(move |i| (i, j)))
+            .filter(|(i, j)| shape.contains(*i, *j, size))
+            .map(|(i, j)| (start + i as isize, start + j as isize))
+            .collect::<Vec<_>>();
+
+        if offsets.is_empty() {
+            offsets.push((0, 0));
+        }
+
+        Self {
+            shape,
+            size,
+            offsets,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn with_size(&self, size: usize) -> Self {
+        Self::new(self.shape.clone(), size)
+    }
+
+    pub fn with_next_shape(&self) -> Self {
+        let shape = match self.shape {
+            Shape::Square => Shape::Round,
+            Shape::Round => Shape::nib(),
+            Shape::Bitmap { .. } => Shape::Square,
+        };
+
+        Self::new(shape, self.size)
+    }
+
+    pub fn stamp<F>(&self, (x, y): (isize, isize), mut put_pixel: F)
+    where
+        F: FnMut(usize, usize),
+    {
+        for (d_x, d_y) in &self.offsets {
+            let (x, y) = (x + d_x, y + d_y);
+
+            if x >= 0 && y >= 0 {
+                put_pixel(x as usize, y as usize);
+            }
+        }
+    }
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self::new(Shape::Square, 1)
+    }
+}
+
+pub struct BrushCursor<'b> {
+    pub brush: &'b Brush,
+    pub position: (isize, isize),
+    pub color: Color,
+}
+
+impl Widget for BrushCursor<'_> {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        self.brush
+            .stamp(self.position, |x, y| buffer.put_pixel(x, y, self.color));
+    }
+}
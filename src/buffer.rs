@@ -1,19 +1,29 @@
-use crate::{color::Color, HEIGHT, WIDTH};
+use crate::{color::Color, viewport::Viewport};
 
 use std::ops::Range;
 
 pub struct Buffer<'p> {
     pixels: &'p mut [u8],
+    width: usize,
+    height: usize,
 }
 
 impl<'p> Buffer<'p> {
-    pub fn new(pixels: &'p mut [u8]) -> Self {
-        Self { pixels }
+    pub fn new(pixels: &'p mut [u8], width: usize, height: usize) -> Self {
+        Self {
+            pixels,
+            width,
+            height,
+        }
     }
 
-    pub fn put_pixel(&mut self, x: usize, y: usize, Color { r, g, b }: Color) {
-        if let Some(ix) = Self::calc_pixel_ix(x, y) {
-            self.pixels[ix..(ix + 4)].copy_from_slice(&[r, g, b, 0xff]);
+    pub fn put_pixel(&mut self, x: usize, y: usize, color: Color) {
+        if let Some(ix) = self.calc_pixel_ix(x, y) {
+            let pixel = &mut self.pixels[ix..(ix + 4)];
+            let below = Color::new(pixel[0], pixel[1], pixel[2]);
+            let Color { r, g, b, .. } = color.over(below);
+
+            pixel.copy_from_slice(&[r, g, b, 0xff]);
         }
     }
 
@@ -24,16 +34,25 @@ impl<'p> Buffer<'p> {
         GuardedBuffer::new(self, guard)
     }
 
-    fn clear(&mut self, Color { r, g, b }: Color) {
+    pub fn lend_viewport<'b>(
+        &'b mut self,
+        viewport: Viewport,
+    ) -> GuardedBuffer<'b, 'p> {
+        let mut buffer = GuardedBuffer::new(self, Box::new(viewport));
+        buffer.viewport = Some(viewport);
+
+        buffer
+    }
+
+    pub fn clear(&mut self, Color { r, g, b, .. }: Color) {
         for pixel in self.pixels.chunks_exact_mut(4) {
             pixel.copy_from_slice(&[r, g, b, 0xff]);
         }
     }
 
-    fn calc_pixel_ix(x: usize, y: usize) -> Option<usize> {
-        if (0..WIDTH as usize).contains(&x) && (0..HEIGHT as usize).contains(&y)
-        {
-            Some((x + y * WIDTH as usize) * 4)
+    fn calc_pixel_ix(&self, x: usize, y: usize) -> Option<usize> {
+        if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
+            Some((x + y * self.width) * 4)
         } else {
             None
         }
@@ -72,17 +91,55 @@ impl Guard for ((usize, usize), (usize, usize)) {
     }
 }
 
+impl Guard for Viewport {
+    fn contains_pixel(&self, x: usize, y: usize) -> bool {
+        self.contains(x, y)
+    }
+}
+
 pub struct GuardedBuffer<'b, 'p> {
     buffer: &'b mut Buffer<'p>,
     guard: Box<dyn Guard>,
+    viewport: Option<Viewport>,
 }
 
 impl<'b, 'p> GuardedBuffer<'b, 'p> {
     pub fn new(buffer: &'b mut Buffer<'p>, guard: Box<dyn Guard>) -> Self {
-        Self { buffer, guard }
+        Self {
+            buffer,
+            guard,
+            viewport: None,
+        }
     }
 
     pub fn put_pixel(&mut self, x: usize, y: usize, color: Color) {
+        match self.viewport {
+            Some(viewport) => {
+                let zoom = viewport.zoom() as isize;
+                let (left, top) =
+                    viewport.canvas_to_frame((x as isize, y as isize));
+
+                for y in top..(top + zoom) {
+                    for x in left..(left + zoom) {
+                        if x >= 0 && y >= 0 {
+                            self.put_frame_pixel(x as usize, y as usize, color);
+                        }
+                    }
+                }
+            }
+            None => self.put_frame_pixel(x, y, color),
+        }
+    }
+
+    pub fn visible(&self) -> Option<(Range<usize>, Range<usize>)> {
+        self.viewport.map(|viewport| viewport.visible())
+    }
+
+    pub fn viewport(&self) -> Option<Viewport> {
+        self.viewport
+    }
+
+    pub fn put_frame_pixel(&mut self, x: usize, y: usize, color: Color) {
         if self.guard.contains_pixel(x, y) {
             self.buffer.put_pixel(x, y, color);
         }
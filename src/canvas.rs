@@ -1,56 +1,488 @@
 use crate::{
     buffer::GuardedBuffer,
     color::Color,
+    document::{Document, LayerChange},
+    history::{PixelChange, Step},
+    selection::{Clip, Selection},
     widget::Widget,
-    BORDER_WIDTH,
 };
 
-pub const CANVAS_WIDTH: u32 = 200;
-pub const CANVAS_HEIGHT: u32 = 100;
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io,
+    io::BufWriter,
+    mem,
+    path::Path,
+};
+
+pub const DEFAULT_CANVAS_WIDTH: usize = 200;
+pub const DEFAULT_CANVAS_HEIGHT: usize = 100;
+const CHECKER_SIZE: usize = 4;
 
 pub struct Canvas {
-    inner: [[Color; CANVAS_WIDTH as usize]; CANVAS_HEIGHT as usize],
+    document: Document,
+    stroke: Option<Stroke>,
+    selection: Option<Selection>,
+    floating: Option<Floating>,
+}
+
+type Stroke = (usize, HashMap<(usize, usize), (Color, Color)>);
+
+struct Floating {
+    clip: Clip,
+    /// Whether lifting the clip changed the active layer, which leaves a
+    /// stroke in the history. Pasted clips and lifts of transparent pixels
+    /// leave none.
+    cleared: bool,
 }
 
 impl Canvas {
-    pub fn new() -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
         Self {
-            inner: [[Color::white(); CANVAS_WIDTH as usize];
-                CANVAS_HEIGHT as usize],
+            document: Document::new(width, height),
+            stroke: None,
+            selection: None,
+            floating: None,
         }
     }
 
+    pub fn width(&self) -> usize {
+        self.document.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.document.height()
+    }
+
+    pub fn document(&self) -> &Document {
+        &self.document
+    }
+
+    pub fn document_mut(&mut self) -> &mut Document {
+        &mut self.document
+    }
+
     pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
-        let pixel = y
-            .checked_sub(BORDER_WIDTH as usize)
-            .and_then(|y| x.checked_sub(BORDER_WIDTH as usize).map(|x| (x, y)))
-            .and_then(|(x, y)| {
-                self.inner.get_mut(y).and_then(|row| row.get_mut(x))
-            });
-        if let Some(pixel) = pixel {
-            *pixel = color;
-        }
+        self.paint_pixel(x, y, |below| color.over(below));
+    }
+
+    pub fn clear_pixel(&mut self, x: usize, y: usize) {
+        self.paint_pixel(x, y, |_| Color::transparent());
     }
 
     pub fn get_pixel(&self, x: usize, y: usize) -> Option<Color> {
-        y.checked_sub(BORDER_WIDTH as usize)
-            .and_then(|y| x.checked_sub(BORDER_WIDTH as usize).map(|x| (x, y)))
-            .and_then(|(x, y)| self.inner.get(y).and_then(|row| row.get(x)))
-            .copied()
+        self.to_inner(x, y)
+            .map(|(x, y)| self.document.composite(x, y))
+    }
+
+    pub fn selection(&self) -> Option<&Selection> {
+        self.selection.as_ref()
+    }
+
+    pub fn set_selection(&mut self, selection: Option<Selection>) {
+        self.selection = selection;
+    }
+
+    pub fn floating(&self) -> Option<&Clip> {
+        self.floating.as_ref().map(|floating| &floating.clip)
+    }
+
+    pub fn copy_selection(&self) -> Option<Clip> {
+        let selection = self.selection.clone()?;
+        let layer = self.document.active_layer();
+
+        Some(Clip::new(selection, |x, y| {
+            if x < 0 || y < 0 {
+                return Color::transparent();
+            }
+
+            self.to_inner(x as usize, y as usize)
+                .map_or(Color::transparent(), |(x, y)| {
+                    layer.pixels[x + y * self.width()]
+                })
+        }))
+    }
+
+    pub fn clear_selection(&mut self) {
+        let selection = match &self.selection {
+            Some(selection) => selection.clone(),
+            None => return,
+        };
+
+        for (x, y) in selection.points() {
+            if x >= 0 && y >= 0 {
+                self.clear_pixel(x as usize, y as usize);
+            }
+        }
+    }
+
+    pub fn lift_selection(&mut self) -> bool {
+        if self.document.active_layer().locked {
+            return false;
+        }
+
+        match self.copy_selection() {
+            Some(clip) => {
+                let transparent = Some(Color::transparent());
+                let cleared = clip
+                    .selection()
+                    .points()
+                    .any(|(x, y)| clip.pixel(x, y) != transparent);
+                self.clear_selection();
+                self.floating = Some(Floating { clip, cleared });
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn paste(&mut self, clip: Clip) {
+        self.selection = Some(clip.selection().clone());
+        self.floating = Some(Floating {
+            clip,
+            cleared: false,
+        });
+    }
+
+    pub fn move_floating(&mut self, delta: (isize, isize)) {
+        if let Some(floating) = &mut self.floating {
+            floating.clip.translate(delta);
+            self.selection = Some(floating.clip.selection().clone());
+        }
+    }
+
+    pub fn map_floating<F>(&mut self, f: F)
+    where
+        F: FnOnce(&Clip) -> Clip,
+    {
+        if let Some(floating) = &mut self.floating {
+            floating.clip = f(&floating.clip);
+            self.selection = Some(floating.clip.selection().clone());
+        }
+    }
+
+    /// Replaces the selected pixels of the active layer in place. Pixels
+    /// that were cleared are overwritten rather than composited onto, since
+    /// a stroke paints over what it found before it started.
+    pub fn map_selection<F>(&mut self, f: F)
+    where
+        F: FnOnce(&Clip) -> Clip,
+    {
+        if self.document.active_layer().locked {
+            return;
+        }
+
+        let clip = match self.copy_selection() {
+            Some(clip) => f(&clip),
+            None => return,
+        };
+        let cleared = match self.selection.take() {
+            Some(selection) => selection,
+            None => return,
+        };
+
+        for (x, y) in cleared.points() {
+            if x >= 0 && y >= 0 {
+                self.clear_pixel(x as usize, y as usize);
+            }
+        }
+
+        for (x, y) in clip.selection().points() {
+            let color = match clip.pixel(x, y) {
+                Some(color) if x >= 0 && y >= 0 => color,
+                _ => continue,
+            };
+
+            if cleared.contains(x, y) {
+                self.paint_pixel(x as usize, y as usize, |_| color);
+            } else {
+                self.paint_pixel(x as usize, y as usize, |below| {
+                    color.over(below)
+                });
+            }
+        }
+
+        self.selection = Some(clip.selection().clone());
+    }
+
+    pub fn commit_floating(&mut self) {
+        let floating = match self.floating.take() {
+            Some(floating) => floating,
+            None => return,
+        };
+
+        for (x, y) in floating.clip.selection().points() {
+            if let Some(color) = floating.clip.pixel(x, y) {
+                if x >= 0 && y >= 0 {
+                    self.set_pixel(x as usize, y as usize, color);
+                }
+            }
+        }
+    }
+
+    /// Drops the floating selection without painting it and returns whether
+    /// lifting it cleared pixels of the active layer.
+    pub fn discard_floating(&mut self) -> Option<bool> {
+        let floating = self.floating.take()?;
+        self.selection = None;
+
+        Some(floating.cleared)
+    }
+
+    pub fn edit_document<F>(&mut self, edit: F) -> Option<Step>
+    where
+        F: FnOnce(&mut Document) -> bool,
+    {
+        let before = self.document.clone();
+
+        if edit(&mut self.document) {
+            Some(Step::Document(before))
+        } else {
+            None
+        }
+    }
+
+    pub fn edit_layers<F>(&mut self, edit: F) -> Option<Step>
+    where
+        F: FnOnce(&mut Document) -> Option<LayerChange>,
+    {
+        let active = self.document.active();
+
+        edit(&mut self.document).map(|change| Step::Layers { change, active })
+    }
+
+    pub fn begin_stroke(&mut self) {
+        self.stroke = Some((self.document.active(), HashMap::new()));
+    }
+
+    pub fn end_stroke(&mut self) -> Option<Step> {
+        let (layer, stroke) = self.stroke.take()?;
+        let changes = stroke
+            .into_iter()
+            .filter(|(_, (before, after))| before != after)
+            .map(|((x, y), (before, after))| PixelChange {
+                x,
+                y,
+                before,
+                after,
+            })
+            .collect::<Vec<_>>();
+
+        if changes.is_empty() {
+            None
+        } else {
+            Some(Step::Pixels { layer, changes })
+        }
+    }
+
+    pub fn undo_step(&mut self, step: &mut Step) {
+        match step {
+            Step::Pixels { layer, changes } => {
+                self.apply_changes(*layer, changes, |change| change.before)
+            }
+            Step::Layers { change, active } => {
+                self.toggle_layers(change, active)
+            }
+            Step::Document(document) => {
+                mem::swap(&mut self.document, document)
+            }
+        }
+    }
+
+    pub fn redo_step(&mut self, step: &mut Step) {
+        match step {
+            Step::Pixels { layer, changes } => {
+                self.apply_changes(*layer, changes, |change| change.after)
+            }
+            Step::Layers { change, active } => {
+                self.toggle_layers(change, active)
+            }
+            Step::Document(document) => {
+                mem::swap(&mut self.document, document)
+            }
+        }
+    }
+
+    pub fn to_png<P: AsRef<Path>>(&self, path: P) -> Result<(), PngError> {
+        let file = File::create(path)?;
+        let mut encoder = png::Encoder::new(
+            BufWriter::new(file),
+            self.width() as u32,
+            self.height() as u32,
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data = (0..self.height())
+            .flat_map(|y| (0..self.width()).map(move |x| (x, y)))
+            .map(|(x, y)| self.document.composite(x, y))
+            .flat_map(|Color { r, g, b, a }| [r, g, b, a])
+            .collect::<Vec<u8>>();
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    pub fn from_png<P: AsRef<Path>>(path: P) -> Result<Self, PngError> {
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        decoder
+            .set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data)?;
+
+        let channels = info.color_type.samples();
+        let (width, height) = (info.width as usize, info.height as usize);
+        let mut pixels = vec![Color::white(); width * height];
+
+        let rows = data.chunks_exact(info.line_size).take(height);
+        for (y, row) in rows.enumerate() {
+            for (x, pixel) in row.chunks_exact(channels).take(width).enumerate()
+            {
+                pixels[x + y * width] = match *pixel {
+                    [v] => Color::new(v, v, v),
+                    [v, a] => Color::rgba(v, v, v, a),
+                    [r, g, b] => Color::new(r, g, b),
+                    [r, g, b, a] => Color::rgba(r, g, b, a),
+                    _ => unreachable!(),
+                };
+            }
+        }
+
+        Ok(Self {
+            document: Document::from_pixels(width, height, pixels),
+            stroke: None,
+            selection: None,
+            floating: None,
+        })
+    }
+
+    fn is_selected(&self, x: usize, y: usize) -> bool {
+        self.selection
+            .as_ref()
+            .is_none_or(|selection| selection.contains(x as isize, y as isize))
+    }
+
+    fn to_inner(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        Some((x, y)).filter(|(x, y)| *x < self.width() && *y < self.height())
+    }
+
+    fn paint_pixel<F>(&mut self, x: usize, y: usize, paint: F)
+    where
+        F: Fn(Color) -> Color,
+    {
+        if !self.is_selected(x, y) {
+            return;
+        }
+
+        if let Some((x, y)) = self.to_inner(x, y) {
+            let width = self.width();
+            let layer = self.document.active_layer_mut();
+            if layer.locked {
+                return;
+            }
+
+            let pixel = &mut layer.pixels[x + y * width];
+            *pixel = match &mut self.stroke {
+                Some((_, stroke)) => {
+                    let change =
+                        stroke.entry((x, y)).or_insert((*pixel, *pixel));
+                    change.1 = paint(change.0);
+
+                    change.1
+                }
+                None => paint(*pixel),
+            };
+        }
+    }
+
+    fn toggle_layers(&mut self, change: &mut LayerChange, active: &mut usize) {
+        let current = self.document.active();
+        self.document.toggle(change);
+        self.document.select(*active);
+        *active = current;
+    }
+
+    fn apply_changes<F>(&mut self, layer: usize, changes: &[PixelChange], f: F)
+    where
+        F: Fn(&PixelChange) -> Color,
+    {
+        let width = self.width();
+
+        if let Some(layer) = self.document.layer_mut(layer) {
+            for change in changes {
+                layer.pixels[change.x + change.y * width] = f(change);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PngError {
+    Io(io::Error),
+    Decoding(png::DecodingError),
+    Encoding(png::EncodingError),
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Decoding(error) => write!(f, "{}", error),
+            Self::Encoding(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for PngError {}
+
+impl From<io::Error> for PngError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<png::DecodingError> for PngError {
+    fn from(error: png::DecodingError) -> Self {
+        Self::Decoding(error)
+    }
+}
+
+impl From<png::EncodingError> for PngError {
+    fn from(error: png::EncodingError) -> Self {
+        Self::Encoding(error)
     }
 }
 
 impl Widget for Canvas {
     fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
-        let offset_x = BORDER_WIDTH as usize;
-        let offset_y = BORDER_WIDTH as usize;
+        let (range_x, range_y) = buffer
+            .visible()
+            .unwrap_or((0..self.width(), 0..self.height()));
+
+        for y in range_y {
+            for x in range_x.clone() {
+                let checker = (x / CHECKER_SIZE + y / CHECKER_SIZE) % 2;
+                let checker = if checker == 0 {
+                    Color::new(0xcc, 0xcc, 0xcc)
+                } else {
+                    Color::white()
+                };
 
-        for (y, row) in self.inner.iter().enumerate() {
-            for (x, pixel) in row.iter().enumerate() {
-                let buffer_x = x + offset_x;
-                let buffer_y = y + offset_y;
+                let color = self.document.composite(x, y);
+                let color = self
+                    .floating()
+                    .and_then(|clip| clip.pixel(x as isize, y as isize))
+                    .map_or(color, |pixel| pixel.over(color));
 
-                buffer.put_pixel(buffer_x, buffer_y, *pixel);
+                buffer.put_pixel(x, y, color.over(checker));
             }
         }
     }
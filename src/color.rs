@@ -3,11 +3,16 @@ pub struct Color {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    pub a: u8,
 }
 
 impl Color {
     pub fn new(r: u8, g: u8, b: u8) -> Self {
-        Self { r, g, b }
+        Self { r, g, b, a: 0xff }
+    }
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
     }
 
     pub fn white() -> Self {
@@ -15,6 +20,7 @@ impl Color {
             r: 0xff,
             g: 0xff,
             b: 0xff,
+            a: 0xff,
         }
     }
 
@@ -23,12 +29,136 @@ impl Color {
             r: 0x00,
             g: 0x00,
             b: 0x00,
+            a: 0xff,
+        }
+    }
+
+    pub fn transparent() -> Self {
+        Self {
+            r: 0x00,
+            g: 0x00,
+            b: 0x00,
+            a: 0x00,
         }
     }
+
+    pub fn fade(self, opacity: u8) -> Self {
+        let a = (self.a as u32 * opacity as u32 + 0x7f) / 0xff;
+
+        Self { a: a as u8, ..self }
+    }
+
+    pub fn over(self, below: Self) -> Self {
+        let src_weight = self.a as u32 * 0xff;
+        let dst_weight = below.a as u32 * (0xff - self.a as u32);
+        let total = src_weight + dst_weight;
+
+        if total == 0 {
+            return Self::transparent();
+        }
+
+        let mix = |src: u8, dst: u8| {
+            let sum = src as u32 * src_weight + dst as u32 * dst_weight;
+
+            ((sum + total / 2) / total) as u8
+        };
+
+        Self {
+            r: mix(self.r, below.r),
+            g: mix(self.g, below.g),
+            b: mix(self.b, below.b),
+            a: ((total + 0x7f) / 0xff) as u8,
+        }
+    }
+
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round();
+
+        Self {
+            r: mix(self.r, other.r) as u8,
+            g: mix(self.g, other.g) as u8,
+            b: mix(self.b, other.b) as u8,
+            a: mix(self.a, other.a) as u8,
+        }
+    }
+
+    pub fn to_hex(self) -> String {
+        let Self { r, g, b, a } = self;
+
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+
+    /// Hue in degrees, saturation and value in `0.0..=1.0`. Alpha is
+    /// ignored.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let channel = |value: u8| value as f32 / 255.0;
+        let (r, g, b) = (channel(self.r), channel(self.g), channel(self.b));
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+
+        let hue = if delta <= 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max <= 0.0 { 0.0 } else { delta / max };
+
+        (hue, saturation, max)
+    }
+
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let channel = |c: f32| ((c + value - chroma) * 255.0).round() as u8;
+
+        Self::new(channel(r), channel(g), channel(b))
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+        // `from_str_radix` would also take a sign, so check the digits here.
+        let digits = hex.chars().all(|c| c.is_ascii_hexdigit());
+        if !digits || (hex.len() != 6 && hex.len() != 8) {
+            return None;
+        }
+
+        let channel = |ix: usize| {
+            hex.get(ix..ix + 2)
+                .map(|digits| u8::from_str_radix(digits, 16).ok())
+        };
+
+        Some(Self {
+            r: channel(0)??,
+            g: channel(2)??,
+            b: channel(4)??,
+            a: channel(6).unwrap_or(Some(0xff))?,
+        })
+    }
 }
 
 impl From<(u8, u8, u8)> for Color {
     fn from((r, g, b): (u8, u8, u8)) -> Self {
-        Self { r, g, b }
+        Self::new(r, g, b)
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for Color {
+    fn from((r, g, b, a): (u8, u8, u8, u8)) -> Self {
+        Self { r, g, b, a }
     }
 }
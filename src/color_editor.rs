@@ -0,0 +1,299 @@
+use crate::{buffer::GuardedBuffer, color::Color, widget::Widget, BORDER_WIDTH};
+
+use winit_input_helper::TextChar;
+
+pub const EDITOR_WIDTH: u32 = 64;
+pub const EDITOR_HEIGHT: u32 =
+    CHANNELS.len() as u32 * (SLIDER_HEIGHT + BORDER_WIDTH) + FIELD_HEIGHT;
+const SLIDER_HEIGHT: u32 = 3;
+const FIELD_HEIGHT: u32 = GLYPH_HEIGHT + 2;
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+const HEX_DIGITS: usize = 8;
+
+const CHANNELS: [Channel; 6] = [
+    Channel::Hue,
+    Channel::Saturation,
+    Channel::Value,
+    Channel::Red,
+    Channel::Green,
+    Channel::Blue,
+];
+
+/// 3x5 glyphs for `#` and the hex digits, one row per byte, most
+/// significant of the three bits on the left.
+const GLYPHS: [(char, [u8; 5]); 17] = [
+    ('#', [0b101, 0b111, 0b101, 0b111, 0b101]),
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b011, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b010, 0b010, 0b010]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('a', [0b010, 0b101, 0b111, 0b101, 0b101]),
+    ('b', [0b110, 0b101, 0b110, 0b101, 0b110]),
+    ('c', [0b011, 0b100, 0b100, 0b100, 0b011]),
+    ('d', [0b110, 0b101, 0b101, 0b101, 0b110]),
+    ('e', [0b111, 0b100, 0b110, 0b100, 0b111]),
+    ('f', [0b111, 0b100, 0b110, 0b100, 0b100]),
+];
+
+#[derive(Clone, Copy)]
+enum Channel {
+    Hue,
+    Saturation,
+    Value,
+    Red,
+    Green,
+    Blue,
+}
+
+/// Hue, saturation, value and RGB sliders above a hex text field. The HSV
+/// triple is kept next to the color so that the hue and the saturation
+/// survive editing grays and black.
+pub struct ColorEditor {
+    top: isize,
+    color: Color,
+    hsv: (f32, f32, f32),
+    dragging: Option<Channel>,
+    /// Digits typed so far while the hex field is focused.
+    hex: Option<String>,
+}
+
+impl ColorEditor {
+    pub fn new(top: isize) -> Self {
+        Self {
+            top,
+            color: Color::black(),
+            hsv: Color::black().to_hsv(),
+            dragging: None,
+            hex: None,
+        }
+    }
+
+    pub fn set_top(&mut self, top: isize) {
+        self.top = top;
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        if color != self.color {
+            self.hsv = keep_hue(self.hsv, color.to_hsv());
+            self.color = color;
+        }
+    }
+
+    pub fn is_typing(&self) -> bool {
+        self.hex.is_some()
+    }
+
+    /// Starts dragging a slider or focuses the hex field. Returns the new
+    /// color if a slider was clicked.
+    pub fn press(&mut self, (x, y): (isize, isize)) -> Option<Color> {
+        self.hex = None;
+
+        if !(0..EDITOR_WIDTH as isize).contains(&(x - Self::left())) {
+            return None;
+        }
+
+        let row = y - self.top;
+        let slot = (SLIDER_HEIGHT + BORDER_WIDTH) as isize;
+        let field = CHANNELS.len() as isize * slot;
+
+        if (0..field).contains(&row) && row % slot < SLIDER_HEIGHT as isize {
+            self.dragging = Some(CHANNELS[(row / slot) as usize]);
+            self.drag((x, y))
+        } else if (field..field + FIELD_HEIGHT as isize).contains(&row) {
+            self.hex = Some(String::new());
+
+            None
+        } else {
+            None
+        }
+    }
+
+    pub fn drag(&mut self, (x, _): (isize, isize)) -> Option<Color> {
+        let channel = self.dragging?;
+        let t = (x - Self::left()) as f32 / (EDITOR_WIDTH - 1) as f32;
+        let (color, hsv) = self.adjusted(channel, t);
+        self.color = color;
+        self.hsv = hsv;
+
+        Some(color)
+    }
+
+    pub fn release(&mut self) {
+        self.dragging = None;
+    }
+
+    /// Feeds typed characters to the hex field. Return and Escape leave
+    /// the field. Returns the new color once six or eight digits parse.
+    pub fn type_text(&mut self, text: &[TextChar]) -> Option<Color> {
+        let hex = self.hex.as_mut()?;
+
+        for c in text {
+            match c {
+                TextChar::Back => {
+                    hex.pop();
+                }
+                TextChar::Char('\r' | '\n' | '\u{1b}') => {
+                    self.hex = None;
+
+                    return None;
+                }
+                TextChar::Char(c)
+                    if c.is_ascii_hexdigit() && hex.len() < HEX_DIGITS =>
+                {
+                    hex.push(c.to_ascii_lowercase());
+                }
+                TextChar::Char(_) => {}
+            }
+        }
+
+        let color = Color::from_hex(hex)?;
+        self.set_color(color);
+
+        Some(color)
+    }
+
+    /// Color and HSV triple after moving `channel` to `t` in `0.0..=1.0`.
+    fn adjusted(&self, channel: Channel, t: f32) -> (Color, (f32, f32, f32)) {
+        let t = t.clamp(0.0, 1.0);
+        let (hue, saturation, value) = self.hsv;
+        let hsv = match channel {
+            Channel::Hue => (t * 360.0, saturation, value),
+            Channel::Saturation => (hue, t, value),
+            Channel::Value => (hue, saturation, t),
+            Channel::Red | Channel::Green | Channel::Blue => {
+                let mut color = self.color;
+                let byte = match channel {
+                    Channel::Red => &mut color.r,
+                    Channel::Green => &mut color.g,
+                    _ => &mut color.b,
+                };
+                *byte = (t * 255.0).round() as u8;
+
+                return (color, keep_hue(self.hsv, color.to_hsv()));
+            }
+        };
+        let (hue, saturation, value) = hsv;
+        let color = Color {
+            a: self.color.a,
+            ..Color::from_hsv(hue, saturation, value)
+        };
+
+        (color, hsv)
+    }
+
+    fn value(&self, channel: Channel) -> f32 {
+        let (hue, saturation, value) = self.hsv;
+
+        match channel {
+            Channel::Hue => hue / 360.0,
+            Channel::Saturation => saturation,
+            Channel::Value => value,
+            Channel::Red => self.color.r as f32 / 255.0,
+            Channel::Green => self.color.g as f32 / 255.0,
+            Channel::Blue => self.color.b as f32 / 255.0,
+        }
+    }
+
+    fn left() -> isize {
+        BORDER_WIDTH as isize
+    }
+}
+
+/// Hue is undefined for grays and saturation for black, so keep the old
+/// ones instead of snapping them to zero.
+fn keep_hue(
+    (old_hue, old_saturation, _): (f32, f32, f32),
+    (hue, saturation, value): (f32, f32, f32),
+) -> (f32, f32, f32) {
+    if value <= 0.0 {
+        (old_hue, old_saturation, value)
+    } else if saturation <= 0.0 {
+        (old_hue, saturation, value)
+    } else {
+        (hue, saturation, value)
+    }
+}
+
+impl Widget for ColorEditor {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        let left = Self::left() as usize;
+        let top = self.top as usize;
+        let width = EDITOR_WIDTH as usize;
+
+        for (i, channel) in CHANNELS.iter().enumerate() {
+            let y = top + i * (SLIDER_HEIGHT + BORDER_WIDTH) as usize;
+            let marker = (self.value(*channel) * (width - 1) as f32).round();
+
+            for x in 0..width {
+                let t = x as f32 / (width - 1) as f32;
+                let (color, _) = self.adjusted(*channel, t);
+                let color = Color { a: 0xff, ..color };
+
+                for dy in 0..SLIDER_HEIGHT as usize {
+                    let color = if x != marker as usize {
+                        color
+                    } else if dy == 1 {
+                        Color::white()
+                    } else {
+                        Color::black()
+                    };
+                    buffer.put_pixel(left + x, y + dy, color);
+                }
+            }
+        }
+
+        let field_top =
+            top + CHANNELS.len() * (SLIDER_HEIGHT + BORDER_WIDTH) as usize;
+        let text = match &self.hex {
+            Some(hex) => format!("#{}", hex),
+            None => self.color.to_hex(),
+        };
+        let field_width = (HEX_DIGITS + 1) * (GLYPH_WIDTH + 1) as usize + 1;
+
+        for y in 0..FIELD_HEIGHT as usize {
+            for x in 0..field_width {
+                buffer.put_pixel(left + x, field_top + y, Color::white());
+            }
+        }
+
+        for (i, c) in text.chars().enumerate() {
+            let x = left + 1 + i * (GLYPH_WIDTH + 1) as usize;
+            draw_glyph(buffer, c, (x, field_top + 1), Color::black());
+        }
+
+        if self.hex.is_some() {
+            let x = left + text.len() * (GLYPH_WIDTH + 1) as usize;
+            let cursor = Color::new(0x80, 0x80, 0x80);
+            for y in 1..=GLYPH_HEIGHT as usize {
+                buffer.put_pixel(x, field_top + y, cursor);
+            }
+        }
+    }
+}
+
+fn draw_glyph(
+    buffer: &mut GuardedBuffer<'_, '_>,
+    c: char,
+    (left, top): (usize, usize),
+    color: Color,
+) {
+    let rows = match GLYPHS.iter().find(|(glyph, _)| *glyph == c) {
+        Some((_, rows)) => rows,
+        None => return,
+    };
+
+    for (y, row) in rows.iter().enumerate() {
+        for x in 0..GLYPH_WIDTH as usize {
+            if row >> (GLYPH_WIDTH as usize - 1 - x) & 1 == 1 {
+                buffer.put_pixel(left + x, top + y, color);
+            }
+        }
+    }
+}
@@ -1,15 +1,11 @@
-use crate::{
-    buffer::GuardedBuffer,
-    canvas::CANVAS_HEIGHT,
-    color::Color,
-    widget::Widget,
-    BORDER_WIDTH,
-};
+use crate::{buffer::GuardedBuffer, color::Color, widget::Widget, BORDER_WIDTH};
 
 pub const BUTTON_SIZE: u32 = 5;
 
 pub struct ColorPicker {
     buttons: Vec<ColorButton>,
+    /// Primary and secondary colors, shown after the buttons.
+    swatches: [ColorButton; 2],
 }
 
 struct ColorButton {
@@ -19,27 +15,55 @@ struct ColorButton {
 }
 
 impl ColorPicker {
-    pub fn new() -> Self {
-        let y = (CANVAS_HEIGHT + 2 * BORDER_WIDTH) as isize;
-        let buttons = vec![
+    pub fn new(y: isize) -> Self {
+        let buttons = [
             (0xff, 0x00, 0x00).into(),
             (0x00, 0x00, 0x00).into(),
             (0x00, 0xff, 0x00).into(),
             (0x00, 0x00, 0xff).into(),
             (0xff, 0xff, 0xff).into(),
+            (0x00, 0x00, 0x00, 0x80).into(),
         ]
         .iter()
         .copied()
         .enumerate()
-        .map(|(i, color)| {
-            let x = (BUTTON_SIZE as isize + BORDER_WIDTH as isize) * i as isize
-                + BORDER_WIDTH as isize;
-
-            ColorButton { color, x, y }
+        .map(|(i, color)| ColorButton {
+            color,
+            x: Self::slot_x(i),
+            y,
         })
-        .collect();
+        .collect::<Vec<_>>();
+
+        // Leave one empty slot between the buttons and the swatches.
+        let swatch = |i: usize, color| ColorButton {
+            color,
+            x: Self::slot_x(buttons.len() + 1 + i),
+            y,
+        };
+        let swatches = [swatch(0, Color::black()), swatch(1, Color::white())];
+
+        Self { buttons, swatches }
+    }
+
+    pub fn width(&self) -> u32 {
+        let slots = self.buttons.len() as u32 + 1 + self.swatches.len() as u32;
+
+        slots * (BUTTON_SIZE + BORDER_WIDTH) - BORDER_WIDTH
+    }
+
+    pub fn colors(&self) -> Vec<Color> {
+        self.buttons.iter().map(|button| button.color).collect()
+    }
+
+    pub fn set_top(&mut self, y: isize) {
+        for button in self.buttons.iter_mut().chain(&mut self.swatches) {
+            button.y = y;
+        }
+    }
 
-        Self { buttons }
+    pub fn set_swatches(&mut self, primary: Color, secondary: Color) {
+        self.swatches[0].color = primary;
+        self.swatches[1].color = secondary;
     }
 
     pub fn pick_color(&mut self, mouse: (isize, isize)) -> Option<Color> {
@@ -51,11 +75,16 @@ impl ColorPicker {
 
         None
     }
+
+    fn slot_x(i: usize) -> isize {
+        (BUTTON_SIZE as isize + BORDER_WIDTH as isize) * i as isize
+            + BORDER_WIDTH as isize
+    }
 }
 
 impl Widget for ColorPicker {
     fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
-        for button in &self.buttons {
+        for button in self.buttons.iter().chain(&self.swatches) {
             button.display(buffer);
         }
     }
@@ -65,11 +94,10 @@ impl Widget for ColorButton {
     fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
         for x in 0..(BUTTON_SIZE as isize) {
             for y in 0..(BUTTON_SIZE as isize) {
-                buffer.put_pixel(
-                    (x + self.x) as usize,
-                    (y + self.y) as usize,
-                    self.color,
-                );
+                let (x, y) = ((x + self.x) as usize, (y + self.y) as usize);
+
+                buffer.put_pixel(x, y, Color::white());
+                buffer.put_pixel(x, y, self.color);
             }
         }
     }
@@ -0,0 +1,112 @@
+use crate::color::Color;
+
+/// How colors that are not in the palette get painted.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Dithering {
+    /// Paint the exact color.
+    Off,
+    /// Snap to the nearest palette color.
+    Nearest,
+    /// Mix the two nearest palette colors with a 4x4 Bayer matrix.
+    Bayer,
+}
+
+impl Dithering {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Nearest,
+            Self::Nearest => Self::Bayer,
+            Self::Bayer => Self::Off,
+        }
+    }
+
+    pub fn apply(
+        self,
+        color: Color,
+        palette: &[Color],
+        (x, y): (usize, usize),
+    ) -> Color {
+        if self == Self::Off {
+            return color;
+        }
+
+        let (a, b) = match nearest_two(color, palette) {
+            Some(nearest) => nearest,
+            None => return color,
+        };
+
+        if self == Self::Nearest || a == b {
+            return a;
+        }
+
+        // Where `color` projects onto the segment between the two nearest
+        // colors decides how many of the threshold cells get `b`.
+        let channels = |c: Color| [c.r, c.g, c.b, c.a].map(|v| v as f32);
+        let (c, a_channels, b_channels) =
+            (channels(color), channels(a), channels(b));
+        let (mut dot, mut length) = (0.0, 0.0);
+        for i in 0..4 {
+            let d = b_channels[i] - a_channels[i];
+            dot += (c[i] - a_channels[i]) * d;
+            length += d * d;
+        }
+
+        if dot / length > bayer_threshold(4, x, y) {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+/// The nearest and the second nearest palette colors, found in a single
+/// pass since this runs for every painted pixel. Ties go to the earlier
+/// color. A one-color palette returns that color twice.
+fn nearest_two(color: Color, palette: &[Color]) -> Option<(Color, Color)> {
+    let mut first: Option<(Color, u32)> = None;
+    let mut second: Option<(Color, u32)> = None;
+
+    for candidate in palette.iter().copied() {
+        let d = distance(color, candidate);
+
+        if first.is_none_or(|(_, best)| d < best) {
+            second = first;
+            first = Some((candidate, d));
+        } else if second.is_none_or(|(_, best)| d < best) {
+            second = Some((candidate, d));
+        }
+    }
+
+    let (a, _) = first?;
+    let (b, _) = second.unwrap_or((a, 0));
+
+    Some((a, b))
+}
+
+/// Threshold in `0.0..1.0` of the Bayer matrix of side `size`, which has to
+/// be a power of two, tiled across the canvas.
+pub fn bayer_threshold(size: usize, x: usize, y: usize) -> f32 {
+    let order = size.trailing_zeros();
+    let mut index = 0;
+
+    for bit in 0..order {
+        let cell = match ((x >> bit) & 1, (y >> bit) & 1) {
+            (0, 0) => 0,
+            (1, 0) => 2,
+            (0, 1) => 3,
+            _ => 1,
+        };
+        index += cell << (2 * (order - 1 - bit));
+    }
+
+    (index as f32 + 0.5) / (size * size) as f32
+}
+
+fn distance(a: Color, b: Color) -> u32 {
+    let diff = |a: u8, b: u8| (a as i32 - b as i32).unsigned_abs();
+
+    [(a.r, b.r), (a.g, b.g), (a.b, b.b), (a.a, b.a)]
+        .iter()
+        .map(|&(a, b)| diff(a, b) * diff(a, b))
+        .sum()
+}
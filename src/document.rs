@@ -0,0 +1,361 @@
+use crate::{color::Color, scale::Scaler, transform::Transform};
+
+use std::mem;
+
+/// Largest width or height of a document. Anything bigger would take
+/// gigabytes per layer and abort on the allocation.
+pub const MAX_SIZE: usize = 8192;
+
+#[derive(Clone)]
+pub struct Layer {
+    pub name: String,
+    pub visible: bool,
+    pub opacity: u8,
+    pub locked: bool,
+    /// Grows with white rather than transparency when the canvas is
+    /// resized. Only the layer a document starts with is a background.
+    pub background: bool,
+    pub pixels: Vec<Color>,
+}
+
+impl Layer {
+    pub fn new(name: String, pixels: Vec<Color>) -> Self {
+        Self {
+            name,
+            visible: true,
+            opacity: 0xff,
+            locked: false,
+            background: false,
+            pixels,
+        }
+    }
+}
+
+/// Change to the layer stack that undoes itself when applied again, the
+/// same way a document snapshot is swapped back and forth.
+pub enum LayerChange {
+    /// Inserts the held layer at `index`, or takes the layer at `index` out
+    /// when none is held.
+    Layer {
+        index: usize,
+        layer: Option<Layer>,
+    },
+    Swap(usize, usize),
+    /// Visibility, opacity and lock of layer `index` from the other side of
+    /// the change.
+    Properties {
+        index: usize,
+        visible: bool,
+        opacity: u8,
+        locked: bool,
+    },
+    /// Layer `index` merged into the one below it. Holds the upper layer
+    /// while it is unmerged and the lower layer's pixels from the other
+    /// side of the merge.
+    Merge {
+        index: usize,
+        upper: Option<Layer>,
+        pixels: Vec<Color>,
+    },
+}
+
+#[derive(Clone)]
+pub struct Document {
+    width: usize,
+    height: usize,
+    layers: Vec<Layer>,
+    active: usize,
+    created: usize,
+}
+
+impl Document {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::from_pixels(width, height, vec![Color::white(); width * height])
+    }
+
+    pub fn from_pixels(
+        width: usize,
+        height: usize,
+        pixels: Vec<Color>,
+    ) -> Self {
+        let background = Layer {
+            background: true,
+            ..Layer::new("Background".to_string(), pixels)
+        };
+
+        Self {
+            width,
+            height,
+            layers: vec![background],
+            active: 0,
+            created: 1,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    pub fn active_layer(&self) -> &Layer {
+        &self.layers[self.active]
+    }
+
+    pub fn active_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layers[self.active]
+    }
+
+    pub fn layer_mut(&mut self, ix: usize) -> Option<&mut Layer> {
+        self.layers.get_mut(ix)
+    }
+
+    pub fn select(&mut self, ix: usize) {
+        self.active = ix.min(self.layers.len() - 1);
+    }
+
+    pub fn add_layer(&mut self) -> Option<LayerChange> {
+        self.created += 1;
+        let layer = Layer::new(
+            format!("Layer {}", self.created),
+            vec![Color::transparent(); self.width * self.height],
+        );
+        self.active += 1;
+        self.layers.insert(self.active, layer);
+
+        Some(LayerChange::Layer {
+            index: self.active,
+            layer: None,
+        })
+    }
+
+    pub fn remove_layer(&mut self) -> Option<LayerChange> {
+        if self.layers.len() == 1 {
+            return None;
+        }
+
+        let index = self.active;
+        let layer = self.layers.remove(index);
+        self.active = self.active.saturating_sub(1);
+
+        Some(LayerChange::Layer {
+            index,
+            layer: Some(layer),
+        })
+    }
+
+    pub fn duplicate_layer(&mut self) -> Option<LayerChange> {
+        let mut layer = self.active_layer().clone();
+        layer.name = format!("{} copy", layer.name);
+        layer.background = false;
+        self.active += 1;
+        self.layers.insert(self.active, layer);
+
+        Some(LayerChange::Layer {
+            index: self.active,
+            layer: None,
+        })
+    }
+
+    pub fn merge_down(&mut self) -> Option<LayerChange> {
+        if self.active == 0 {
+            return None;
+        }
+
+        let index = self.active;
+        let upper = self.layers.remove(index);
+        self.active -= 1;
+        let lower = &mut self.layers[self.active];
+        let pixels = lower.pixels.clone();
+
+        if upper.visible {
+            for (below, above) in lower.pixels.iter_mut().zip(&upper.pixels) {
+                *below = above.fade(upper.opacity).over(*below);
+            }
+        }
+
+        Some(LayerChange::Merge {
+            index,
+            upper: Some(upper),
+            pixels,
+        })
+    }
+
+    pub fn raise_layer(&mut self) -> Option<LayerChange> {
+        if self.active + 1 == self.layers.len() {
+            return None;
+        }
+
+        self.layers.swap(self.active, self.active + 1);
+        self.active += 1;
+
+        Some(LayerChange::Swap(self.active - 1, self.active))
+    }
+
+    pub fn lower_layer(&mut self) -> Option<LayerChange> {
+        if self.active == 0 {
+            return None;
+        }
+
+        self.layers.swap(self.active, self.active - 1);
+        self.active -= 1;
+
+        Some(LayerChange::Swap(self.active, self.active + 1))
+    }
+
+    pub fn toggle_visibility(&mut self) -> Option<LayerChange> {
+        self.edit_properties(|layer| layer.visible = !layer.visible)
+    }
+
+    pub fn toggle_lock(&mut self) -> Option<LayerChange> {
+        self.edit_properties(|layer| layer.locked = !layer.locked)
+    }
+
+    pub fn change_opacity(&mut self, delta: i16) -> Option<LayerChange> {
+        self.edit_properties(|layer| {
+            layer.opacity = (layer.opacity as i16 + delta).clamp(0, 0xff) as u8;
+        })
+    }
+
+    /// Applies `change` and leaves it ready to revert what it just did.
+    pub fn toggle(&mut self, change: &mut LayerChange) {
+        match change {
+            LayerChange::Layer { index, layer } => match layer.take() {
+                Some(held) => self.layers.insert(*index, held),
+                None => *layer = Some(self.layers.remove(*index)),
+            },
+            LayerChange::Swap(a, b) => self.layers.swap(*a, *b),
+            LayerChange::Properties {
+                index,
+                visible,
+                opacity,
+                locked,
+            } => {
+                let layer = &mut self.layers[*index];
+                mem::swap(&mut layer.visible, visible);
+                mem::swap(&mut layer.opacity, opacity);
+                mem::swap(&mut layer.locked, locked);
+            }
+            LayerChange::Merge {
+                index,
+                upper,
+                pixels,
+            } => {
+                mem::swap(&mut self.layers[*index - 1].pixels, pixels);
+                match upper.take() {
+                    Some(held) => self.layers.insert(*index, held),
+                    None => *upper = Some(self.layers.remove(*index)),
+                }
+            }
+        }
+    }
+
+    fn edit_properties<F>(&mut self, edit: F) -> Option<LayerChange>
+    where
+        F: FnOnce(&mut Layer),
+    {
+        let index = self.active;
+        let layer = &mut self.layers[index];
+        let before = (layer.visible, layer.opacity, layer.locked);
+        edit(layer);
+
+        if before == (layer.visible, layer.opacity, layer.locked) {
+            return None;
+        }
+
+        let (visible, opacity, locked) = before;
+
+        Some(LayerChange::Properties {
+            index,
+            visible,
+            opacity,
+            locked,
+        })
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) -> bool {
+        if (width, height) == (self.width, self.height)
+            || !is_valid_size((width, height))
+        {
+            return false;
+        }
+
+        for layer in &mut self.layers {
+            let fill = if layer.background {
+                Color::white()
+            } else {
+                Color::transparent()
+            };
+            let mut pixels = vec![fill; width * height];
+
+            for y in 0..self.height.min(height) {
+                for x in 0..self.width.min(width) {
+                    pixels[x + y * width] = layer.pixels[x + y * self.width];
+                }
+            }
+
+            layer.pixels = pixels;
+        }
+
+        self.width = width;
+        self.height = height;
+
+        true
+    }
+
+    pub fn transform(&mut self, transform: Transform) -> bool {
+        let size = (self.width, self.height);
+
+        for layer in &mut self.layers {
+            layer.pixels = transform.apply(size, &layer.pixels);
+        }
+
+        let (width, height) = transform.size(size);
+        self.width = width;
+        self.height = height;
+
+        true
+    }
+
+    pub fn scale(&mut self, scaler: Scaler) -> bool {
+        let size = (self.width, self.height);
+        if scaler.size(size) == size || !is_valid_size(scaler.size(size)) {
+            return false;
+        }
+
+        for layer in &mut self.layers {
+            layer.pixels = scaler.apply(size, &layer.pixels);
+        }
+
+        let (width, height) = scaler.size(size);
+        self.width = width;
+        self.height = height;
+
+        true
+    }
+
+    pub fn composite(&self, x: usize, y: usize) -> Color {
+        let ix = x + y * self.width;
+
+        self.layers
+            .iter()
+            .filter(|layer| layer.visible)
+            .fold(Color::transparent(), |below, layer| {
+                layer.pixels[ix].fade(layer.opacity).over(below)
+            })
+    }
+}
+
+pub fn is_valid_size((width, height): (usize, usize)) -> bool {
+    (1..=MAX_SIZE).contains(&width) && (1..=MAX_SIZE).contains(&height)
+}
@@ -0,0 +1,66 @@
+use crate::{buffer::GuardedBuffer, color::Color, widget::Widget};
+
+const MIN_PIXEL_GRID_ZOOM: usize = 4;
+const DEFAULT_TILE_SIZE: (usize, usize) = (16, 16);
+
+pub struct Grid {
+    pub pixel_grid: bool,
+    pub tile_grid: bool,
+    pub tile_size: (usize, usize),
+    pub pixel_color: Color,
+    pub tile_color: Color,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            pixel_grid: false,
+            tile_grid: false,
+            tile_size: DEFAULT_TILE_SIZE,
+            pixel_color: Color::rgba(0x80, 0x80, 0x80, 0x60),
+            tile_color: Color::rgba(0x00, 0x80, 0xff, 0xa0),
+        }
+    }
+}
+
+impl Widget for Grid {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        let viewport = match buffer.viewport() {
+            Some(viewport) => viewport,
+            None => return,
+        };
+        let pixel_grid =
+            self.pixel_grid && viewport.zoom() >= MIN_PIXEL_GRID_ZOOM;
+        let (range_x, range_y) = viewport.visible();
+        let ((left, top), (width, height)) =
+            (viewport.origin(), viewport.size());
+
+        let color = |i: usize, tile: usize| {
+            if self.tile_grid && i.is_multiple_of(tile) {
+                Some(self.tile_color)
+            } else if pixel_grid {
+                Some(self.pixel_color)
+            } else {
+                None
+            }
+        };
+
+        for x in range_x {
+            if let Some(color) = color(x, self.tile_size.0) {
+                let (frame_x, _) = viewport.canvas_to_frame((x as isize, 0));
+                for frame_y in top..(top + height) {
+                    buffer.put_frame_pixel(frame_x as usize, frame_y, color);
+                }
+            }
+        }
+
+        for y in range_y {
+            if let Some(color) = color(y, self.tile_size.1) {
+                let (_, frame_y) = viewport.canvas_to_frame((0, y as isize));
+                for frame_x in left..(left + width) {
+                    buffer.put_frame_pixel(frame_x, frame_y as usize, color);
+                }
+            }
+        }
+    }
+}
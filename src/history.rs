@@ -0,0 +1,106 @@
+use crate::{
+    color::Color,
+    document::{Document, Layer, LayerChange},
+};
+
+use std::mem;
+
+const MAX_STEPS: usize = 256;
+/// Document snapshots are as big as the whole image, so the step count
+/// alone does not bound memory.
+const MAX_BYTES: usize = 256 << 20;
+
+#[derive(Copy, Clone, Debug)]
+pub struct PixelChange {
+    pub x: usize,
+    pub y: usize,
+    pub before: Color,
+    pub after: Color,
+}
+
+pub enum Step {
+    Pixels {
+        layer: usize,
+        changes: Vec<PixelChange>,
+    },
+    /// Layer stack change and the active layer on the other side of it.
+    Layers {
+        change: LayerChange,
+        active: usize,
+    },
+    Document(Document),
+}
+
+impl Step {
+    fn size(&self) -> usize {
+        let layer_size =
+            |layer: &Layer| layer.pixels.len() * mem::size_of::<Color>();
+
+        match self {
+            Self::Pixels { changes, .. } => {
+                changes.len() * mem::size_of::<PixelChange>()
+            }
+            Self::Layers { change, .. } => match change {
+                LayerChange::Layer { layer, .. } => {
+                    layer.as_ref().map_or(0, layer_size)
+                }
+                LayerChange::Swap(..) | LayerChange::Properties { .. } => 0,
+                LayerChange::Merge { upper, pixels, .. } => {
+                    upper.as_ref().map_or(0, layer_size)
+                        + pixels.len() * mem::size_of::<Color>()
+                }
+            },
+            Self::Document(document) => {
+                document.layers().iter().map(layer_size).sum()
+            }
+        }
+    }
+}
+
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            undo: vec![],
+            redo: vec![],
+        }
+    }
+
+    pub fn push(&mut self, step: Step) {
+        self.redo.clear();
+        self.undo.push(step);
+
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+
+        // Always keep the newest step, however big, so it can be undone.
+        let mut size = self.undo.iter().map(Step::size).sum::<usize>();
+        while size > MAX_BYTES && self.undo.len() > 1 {
+            size -= self.undo.remove(0).size();
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<&mut Step> {
+        let step = self.undo.pop()?;
+        self.redo.push(step);
+
+        self.redo.last_mut()
+    }
+
+    pub fn redo(&mut self) -> Option<&mut Step> {
+        let step = self.redo.pop()?;
+        self.undo.push(step);
+
+        self.undo.last_mut()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
@@ -1,4 +1,4 @@
-#![feature(box_syntax)]
+#![cfg_attr(all(test, feature = "bench"), feature(test))]
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
@@ -11,35 +11,87 @@ use winit::{
 };
 use winit_input_helper::WinitInputHelper;
 
+use std::process;
+
 mod app;
+mod args;
+mod brush;
 mod buffer;
 mod canvas;
 mod color;
+mod color_editor;
 mod color_picker;
+mod dither;
+mod document;
+mod grid;
+mod history;
+mod pattern;
+mod region;
+mod scale;
+mod selection;
 mod tools;
+mod transform;
 mod utils;
+mod viewport;
 mod widget;
 
-use app::App;
-use canvas::{CANVAS_HEIGHT, CANVAS_WIDTH};
-use tools::{Circe, Linen, Penicilin, Phill, Rectangel};
+use app::{App, Button};
+use args::Args;
+use canvas::{DEFAULT_CANVAS_HEIGHT, DEFAULT_CANVAS_WIDTH};
+use tools::{
+    Circe, Ellipsis, Erazor, Grady, Lassie, Linen, Markee, Modifiers,
+    Penicilin, Phill, Pipette, Rectangel, Wanda,
+};
+use scale::Scaler;
+use transform::Transform;
 
 const BORDER_WIDTH: u32 = 1;
-const COLOR_PICKER_SIZE: u32 = 5;
-const WIDTH: u32 = CANVAS_WIDTH + 2 * BORDER_WIDTH;
-const HEIGHT: u32 = CANVAS_HEIGHT + 3 * BORDER_WIDTH + COLOR_PICKER_SIZE;
 const PIXEL_SCALE: f64 = 4.0;
+const RESIZE_STEP: usize = 8;
+const OPACITY_STEP: i16 = 0x19;
+const TOLERANCE_STEP: i16 = 0x08;
 
 fn main() -> Result<(), Error> {
+    let Args {
+        path,
+        size,
+        tile_size,
+        tile_color,
+    } = Args::parse().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(2);
+    });
+
+    let mut app = {
+        let (width, height) =
+            size.unwrap_or((DEFAULT_CANVAS_WIDTH, DEFAULT_CANVAS_HEIGHT));
+        App::new(width, height)
+    };
+
+    if let Some(tile_size) = tile_size {
+        app.set_tile_size(tile_size);
+    }
+    if let Some(tile_color) = tile_color {
+        app.set_tile_color(tile_color);
+    }
+
+    if path.exists() {
+        if let Err(error) = app.load(&path) {
+            eprintln!("could not load {}: {}", path.display(), error);
+        }
+        if let Some((width, height)) = size {
+            app.resize_canvas(width, height);
+        }
+    }
+
     let event_loop = EventLoop::new();
     let mut input = WinitInputHelper::new();
+    let mut frame_size = app.frame_size();
+    let mut window_title = title(&app);
     let window = {
-        let size = LogicalSize::new(
-            WIDTH as f64 * PIXEL_SCALE,
-            HEIGHT as f64 * PIXEL_SCALE,
-        );
+        let size = window_size(frame_size);
         WindowBuilder::new()
-            .with_title("pain... t")
+            .with_title(&window_title)
             .with_inner_size(size)
             .with_min_inner_size(size)
             .build(&event_loop)
@@ -50,9 +102,8 @@ fn main() -> Result<(), Error> {
         let window_size = window.inner_size();
         let surface_texture =
             SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(WIDTH, HEIGHT, surface_texture)?
+        Pixels::new(frame_size.0, frame_size.1, surface_texture)?
     };
-    let mut app = App::new();
 
     event_loop.run(move |event, _, control_flow| {
         if let Event::RedrawRequested(_) = event {
@@ -98,27 +149,263 @@ fn main() -> Result<(), Error> {
                 })
                 .unwrap_or_default();
 
-            if input.mouse_pressed(0) {
-                app.handle_press(mouse_cell);
-            } else if input.mouse_held(0) {
+            app.set_modifiers(Modifiers {
+                shift: input.held_shift(),
+                ctrl: input.held_control(),
+                alt: input.held_alt(),
+            });
+            app.handle_move(mouse_cell);
+
+            let panning = input.mouse_held(2)
+                || (input.key_held(VirtualKeyCode::Space) && !app.is_pressed());
+
+            if panning {
+                if input.mouse_held(0) || input.mouse_held(2) {
+                    app.pan((
+                        mouse_cell.0 - prev_mouse_cell.0,
+                        mouse_cell.1 - prev_mouse_cell.1,
+                    ));
+                }
+            } else if input.mouse_pressed(0) {
+                app.handle_press(mouse_cell, Button::Primary);
+            } else if input.mouse_pressed(1) {
+                app.handle_press(mouse_cell, Button::Secondary);
+            } else if input.mouse_held(0) || input.mouse_held(1) {
                 app.handle_hold(prev_mouse_cell, mouse_cell);
-            } else if input.mouse_released(0) {
+            } else if input.mouse_released(0) || input.mouse_released(1) {
                 app.handle_release(mouse_cell);
             }
 
-            if input.key_pressed(VirtualKeyCode::Key1) {
-                app.switch_tool(box Penicilin::new());
-            } else if input.key_pressed(VirtualKeyCode::Key2) {
-                app.switch_tool(box Rectangel::new());
-            } else if input.key_pressed(VirtualKeyCode::Key3) {
-                app.switch_tool(box Circe::new());
-            } else if input.key_pressed(VirtualKeyCode::Key4) {
-                app.switch_tool(box Linen::new());
-            } else if input.key_pressed(VirtualKeyCode::Key5) {
-                app.switch_tool(box Phill::new());
+            if app.is_typing() {
+                app.type_text(&input.text());
+            } else {
+                let ctrl = input.held_control();
+                let resize_step =
+                    if input.held_shift() { 1 } else { RESIZE_STEP };
+
+                if ctrl && input.key_pressed(VirtualKeyCode::S) {
+                    match app.save(&path) {
+                        Ok(()) => println!("saved {}", path.display()),
+                        Err(error) => eprintln!(
+                            "could not save {}: {}",
+                            path.display(),
+                            error
+                        ),
+                    }
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Z) {
+                    if input.held_shift() {
+                        app.redo();
+                    } else {
+                        app.undo();
+                    }
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Right) {
+                    let (width, height) = app.canvas_size();
+                    app.resize_canvas(width + resize_step, height);
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Left) {
+                    let (width, height) = app.canvas_size();
+                    app.resize_canvas(
+                        width.saturating_sub(resize_step),
+                        height,
+                    );
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Down) {
+                    let (width, height) = app.canvas_size();
+                    app.resize_canvas(width, height + resize_step);
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Up) {
+                    let (width, height) = app.canvas_size();
+                    app.resize_canvas(
+                        width,
+                        height.saturating_sub(resize_step),
+                    );
+                } else if ctrl && input.key_pressed(VirtualKeyCode::N) {
+                    app.add_layer();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Delete) {
+                    app.remove_layer();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::J) {
+                    app.duplicate_layer();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::E) {
+                    app.merge_layer_down();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::PageUp) {
+                    app.raise_layer();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::PageDown) {
+                    app.lower_layer();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::H) {
+                    app.toggle_layer_visibility();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::L) {
+                    app.toggle_layer_lock();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Comma) {
+                    app.change_layer_opacity(-OPACITY_STEP);
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Period) {
+                    app.change_layer_opacity(OPACITY_STEP);
+                } else if input.key_pressed(VirtualKeyCode::PageUp) {
+                    app.select_layer(1);
+                } else if input.key_pressed(VirtualKeyCode::PageDown) {
+                    app.select_layer(-1);
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Key1) {
+                    app.scale(Scaler::Nearest(0.5));
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Key2) {
+                    app.scale(if input.held_shift() {
+                        Scaler::Scale2x
+                    } else {
+                        Scaler::Nearest(2.0)
+                    });
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Key3) {
+                    app.scale(if input.held_shift() {
+                        Scaler::Scale3x
+                    } else {
+                        Scaler::Nearest(3.0)
+                    });
+                } else if ctrl && input.key_pressed(VirtualKeyCode::Key4) {
+                    app.scale(Scaler::Nearest(4.0));
+                } else if ctrl && input.key_pressed(VirtualKeyCode::C) {
+                    app.copy();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::X) {
+                    app.cut();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::V) {
+                    app.paste();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::A) {
+                    app.select_all();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::D) {
+                    app.deselect();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::I) {
+                    app.invert_selection();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::K) {
+                    app.pattern_from_selection();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::RBracket) {
+                    app.grow_selection(1);
+                } else if ctrl && input.key_pressed(VirtualKeyCode::LBracket) {
+                    app.shrink_selection(1);
+                } else if input.key_pressed(VirtualKeyCode::Return) {
+                    app.commit_floating();
+                } else if input.key_pressed(VirtualKeyCode::Escape) {
+                    app.deselect();
+                } else if ctrl && input.key_pressed(VirtualKeyCode::O) {
+                    match app.load(&path) {
+                        Ok(()) => println!("loaded {}", path.display()),
+                        Err(error) => eprintln!(
+                            "could not load {}: {}",
+                            path.display(),
+                            error
+                        ),
+                    }
+                }
+
+                if !ctrl && input.key_pressed(VirtualKeyCode::Key1) {
+                    app.switch_tool(Box::new(Penicilin::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key2) {
+                    app.switch_tool(Box::new(Rectangel::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key3) {
+                    app.switch_tool(Box::new(Circe::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key4) {
+                    app.switch_tool(Box::new(Linen::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key5) {
+                    app.switch_tool(Box::new(Phill::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key6) {
+                    app.switch_tool(Box::new(Pipette::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key7) {
+                    app.switch_tool(Box::new(Erazor::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key8) {
+                    app.switch_tool(Box::new(Ellipsis::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key9) {
+                    app.switch_tool(Box::new(Markee::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Key0) {
+                    app.switch_tool(Box::new(Wanda::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::L) {
+                    app.switch_tool(Box::new(Lassie::new()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::P) {
+                    app.switch_tool(Box::new(Lassie::polygon()));
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Q) {
+                    if input.held_shift() {
+                        app.switch_tool(Box::new(Grady::radial()));
+                    } else {
+                        app.switch_tool(Box::new(Grady::linear()));
+                    }
+                }
+
+                if !ctrl && input.key_pressed(VirtualKeyCode::LBracket) {
+                    app.change_brush_size(-1);
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::RBracket) {
+                    app.change_brush_size(1);
+                } else if input.key_pressed(VirtualKeyCode::B) {
+                    app.cycle_brush_shape();
+                } else if input.key_pressed(VirtualKeyCode::F) {
+                    app.cycle_fill_mode();
+                } else if input.key_pressed(VirtualKeyCode::G) {
+                    app.toggle_pixel_grid();
+                } else if input.key_pressed(VirtualKeyCode::T) {
+                    app.toggle_tile_grid();
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Comma) {
+                    app.change_tolerance(-TOLERANCE_STEP);
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::Period) {
+                    app.change_tolerance(TOLERANCE_STEP);
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::C) {
+                    app.toggle_connectivity();
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::A) {
+                    app.toggle_contiguous();
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::M) {
+                    app.toggle_distance();
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::D) {
+                    app.cycle_dithering();
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::X) {
+                    app.swap_colors();
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::K) {
+                    app.cycle_pattern();
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::I) {
+                    let delta = if input.held_shift() { -1 } else { 1 };
+                    app.change_pattern_level(delta);
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::H) {
+                    app.transform(Transform::FlipHorizontal);
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::V) {
+                    app.transform(Transform::FlipVertical);
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::R) {
+                    app.transform(if input.held_shift() {
+                        Transform::RotateCounterClockwise
+                    } else {
+                        Transform::RotateClockwise
+                    });
+                } else if !ctrl && input.key_pressed(VirtualKeyCode::U) {
+                    app.transform(Transform::Rotate180);
+                } else if input.key_pressed(VirtualKeyCode::Equals) {
+                    app.zoom_in(mouse_cell);
+                } else if input.key_pressed(VirtualKeyCode::Minus) {
+                    app.zoom_out(mouse_cell);
+                }
+            }
+
+            if input.scroll_diff() > 0.0 {
+                app.zoom_in(mouse_cell);
+            } else if input.scroll_diff() < 0.0 {
+                app.zoom_out(mouse_cell);
+            }
+
+            if app.frame_size() != frame_size {
+                frame_size = app.frame_size();
+                pixels.resize_buffer(frame_size.0, frame_size.1);
+
+                let size = window_size(frame_size);
+                window.set_min_inner_size(Some(size));
+                window.set_inner_size(size);
+            }
+
+            if title(&app) != window_title {
+                window_title = title(&app);
+                window.set_title(&window_title);
             }
 
             window.request_redraw();
         }
     });
 }
+
+fn title(app: &App) -> String {
+    format!(
+        "pain... t - {} - {} - {} - {}x",
+        app.layer_status(),
+        app.fill_status(),
+        app.region_status(),
+        app.zoom()
+    )
+}
+
+fn window_size((width, height): (u32, u32)) -> LogicalSize<f64> {
+    LogicalSize::new(width as f64 * PIXEL_SCALE, height as f64 * PIXEL_SCALE)
+}
@@ -0,0 +1,94 @@
+use crate::{color::Color, dither, selection::Clip};
+
+const BAYER_SIZES: [usize; 3] = [2, 4, 8];
+
+/// Tile repeated across the canvas from its origin, so that separate fills
+/// with the same pattern line up.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub enum Pattern {
+    #[default]
+    Solid,
+    /// Paints the cells of a Bayer matrix whose threshold is below
+    /// `level / size²` and leaves the rest untouched.
+    Bayer { size: usize, level: usize },
+    Custom {
+        width: usize,
+        height: usize,
+        pixels: Vec<Color>,
+    },
+}
+
+impl Pattern {
+    pub fn bayer(size: usize) -> Self {
+        Self::Bayer {
+            size,
+            level: size * size / 2,
+        }
+    }
+
+    pub fn from_clip(clip: &Clip) -> Self {
+        let ((left, top), (width, height)) = (clip.origin(), clip.size());
+        let pixels = (0..height as isize)
+            .flat_map(|y| (0..width as isize).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                // Shift the tile so that it lines up with where it was
+                // taken from.
+                let x = left + (x - left).rem_euclid(width as isize);
+                let y = top + (y - top).rem_euclid(height as isize);
+
+                clip.pixel(x, y).unwrap_or_else(Color::transparent)
+            })
+            .collect();
+
+        Self::Custom {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Cycles through solid and the Bayer patterns. Custom patterns go back
+    /// to solid.
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Solid => Self::bayer(BAYER_SIZES[0]),
+            Self::Bayer { size, .. } => BAYER_SIZES
+                .iter()
+                .skip_while(|s| *s != size)
+                .nth(1)
+                .map_or(Self::Solid, |size| Self::bayer(*size)),
+            Self::Custom { .. } => Self::Solid,
+        }
+    }
+
+    pub fn with_level_offset(&self, offset: isize) -> Self {
+        match *self {
+            Self::Bayer { size, level } => {
+                let level = level as isize + offset;
+
+                Self::Bayer {
+                    size,
+                    level: level.clamp(1, (size * size) as isize - 1) as usize,
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+
+    pub fn paint(&self, color: Color, (x, y): (usize, usize)) -> Option<Color> {
+        match self {
+            Self::Solid => Some(color),
+            Self::Bayer { size, level } => {
+                let threshold = dither::bayer_threshold(*size, x, y);
+                let density = *level as f32 / (size * size) as f32;
+
+                (threshold < density).then_some(color)
+            }
+            Self::Custom {
+                width,
+                height,
+                pixels,
+            } => Some(pixels[x % width + (y % height) * width]),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Scaler {
+    Nearest(f32),
+    Scale2x,
+    Scale3x,
+}
+
+impl Scaler {
+    pub fn size(self, (width, height): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::Nearest(factor) => {
+                let scale = |n: usize| (n as f32 * factor).round() as usize;
+                (scale(width).max(1), scale(height).max(1))
+            }
+            Self::Scale2x => (width * 2, height * 2),
+            Self::Scale3x => (width * 3, height * 3),
+        }
+    }
+
+    pub fn apply<T>(self, size: (usize, usize), pixels: &[T]) -> Vec<T>
+    where
+        T: Copy + PartialEq,
+    {
+        match self {
+            Self::Nearest(_) => nearest(size, self.size(size), pixels),
+            Self::Scale2x => scale2x(size, pixels),
+            Self::Scale3x => scale3x(size, pixels),
+        }
+    }
+}
+
+fn nearest<T: Copy>(
+    (width, height): (usize, usize),
+    (new_width, new_height): (usize, usize),
+    pixels: &[T],
+) -> Vec<T> {
+    (0..new_height)
+        .flat_map(|y| (0..new_width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let x = x * width / new_width;
+            let y = y * height / new_height;
+
+            pixels[x + y * width]
+        })
+        .collect()
+}
+
+/// Returns the 3x3 neighbourhood of a pixel, row by row, repeating the
+/// edge pixels outside of the image.
+fn neighbourhood<T: Copy>(
+    (width, height): (usize, usize),
+    pixels: &[T],
+    (x, y): (usize, usize),
+) -> [T; 9] {
+    let xs = [x.saturating_sub(1), x, (x + 1).min(width - 1)];
+    let ys = [y.saturating_sub(1), y, (y + 1).min(height - 1)];
+    let at = |i: usize| pixels[xs[i % 3] + ys[i / 3] * width];
+
+    [at(0), at(1), at(2), at(3), at(4), at(5), at(6), at(7), at(8)]
+}
+
+/// Scale2x, also known as EPX.
+fn scale2x<T>((width, height): (usize, usize), pixels: &[T]) -> Vec<T>
+where
+    T: Copy + PartialEq,
+{
+    let mut scaled = vec![pixels[0]; width * height * 4];
+
+    for y in 0..height {
+        for x in 0..width {
+            let [_, b, _, d, e, f, _, h, _] =
+                neighbourhood((width, height), pixels, (x, y));
+            let edge = b != h && d != f;
+            let block = [
+                if edge && d == b { d } else { e },
+                if edge && b == f { f } else { e },
+                if edge && d == h { d } else { e },
+                if edge && h == f { f } else { e },
+            ];
+
+            for (i, color) in block.iter().enumerate() {
+                let (scaled_x, scaled_y) = (x * 2 + i % 2, y * 2 + i / 2);
+                scaled[scaled_x + scaled_y * width * 2] = *color;
+            }
+        }
+    }
+
+    scaled
+}
+
+fn scale3x<T>((width, height): (usize, usize), pixels: &[T]) -> Vec<T>
+where
+    T: Copy + PartialEq,
+{
+    let mut scaled = vec![pixels[0]; width * height * 9];
+
+    for y in 0..height {
+        for x in 0..width {
+            let [a, b, c, d, e, f, g, h, i] =
+                neighbourhood((width, height), pixels, (x, y));
+            let edge = b != h && d != f;
+            let block = [
+                if edge && d == b { d } else { e },
+                if edge && ((d == b && e != c) || (b == f && e != a)) {
+                    b
+                } else {
+                    e
+                },
+                if edge && b == f { f } else { e },
+                if edge && ((d == b && e != g) || (d == h && e != a)) {
+                    d
+                } else {
+                    e
+                },
+                e,
+                if edge && ((b == f && e != i) || (h == f && e != c)) {
+                    f
+                } else {
+                    e
+                },
+                if edge && d == h { d } else { e },
+                if edge && ((d == h && e != i) || (h == f && e != g)) {
+                    h
+                } else {
+                    e
+                },
+                if edge && h == f { f } else { e },
+            ];
+
+            for (j, color) in block.iter().enumerate() {
+                let (scaled_x, scaled_y) = (x * 3 + j % 3, y * 3 + j / 3);
+                scaled[scaled_x + scaled_y * width * 3] = *color;
+            }
+        }
+    }
+
+    scaled
+}
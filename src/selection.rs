@@ -0,0 +1,322 @@
+use crate::{
+    buffer::GuardedBuffer,
+    color::Color,
+    scale::Scaler,
+    transform::Transform,
+    widget::Widget,
+};
+
+const ANT_LENGTH: isize = 4;
+
+#[derive(Clone)]
+pub struct Selection {
+    origin: (isize, isize),
+    width: usize,
+    height: usize,
+    mask: Vec<bool>,
+}
+
+impl Selection {
+    pub fn rect(start: (isize, isize), end: (isize, isize)) -> Self {
+        let origin = (start.0.min(end.0), start.1.min(end.1));
+        let width = (start.0 - end.0).unsigned_abs() + 1;
+        let height = (start.1 - end.1).unsigned_abs() + 1;
+
+        Self {
+            origin,
+            width,
+            height,
+            mask: vec![true; width * height],
+        }
+    }
+
+    pub fn all((width, height): (usize, usize)) -> Self {
+        Self::from_mask((0, 0), (width, height), vec![true; width * height])
+    }
+
+    pub fn from_mask(
+        origin: (isize, isize),
+        (width, height): (usize, usize),
+        mask: Vec<bool>,
+    ) -> Self {
+        assert_eq!(mask.len(), width * height);
+
+        Self {
+            origin,
+            width,
+            height,
+            mask,
+        }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.mask.contains(&true)
+    }
+
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        self.to_inner(x, y).is_some_and(|ix| self.mask[ix])
+    }
+
+    pub fn translate(&mut self, (dx, dy): (isize, isize)) {
+        self.origin = (self.origin.0 + dx, self.origin.1 + dy);
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        let (left, top) = self.origin;
+
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(move |(x, y)| self.mask[x + y * self.width])
+            .map(move |(x, y)| (left + x as isize, top + y as isize))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a || b)
+    }
+
+    pub fn subtract(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a && !b)
+    }
+
+    pub fn invert(&self, (width, height): (usize, usize)) -> Self {
+        let mask = (0..height as isize)
+            .flat_map(|y| (0..width as isize).map(move |x| (x, y)))
+            .map(|(x, y)| !self.contains(x, y))
+            .collect();
+
+        Self::from_mask((0, 0), (width, height), mask)
+    }
+
+    pub fn grow(&self, n: usize) -> Self {
+        self.morph(n, true)
+    }
+
+    pub fn shrink(&self, n: usize) -> Self {
+        self.morph(n, false)
+    }
+
+    /// Dilates or erodes the mask with a square of side `2 * n + 1`, one
+    /// axis at a time.
+    fn morph(&self, n: usize, grow: bool) -> Self {
+        let n = n as isize;
+        let pad = if grow { n } else { 0 };
+        let (left, top) = (self.origin.0 - pad, self.origin.1 - pad);
+        let width = self.width + 2 * pad as usize;
+        let height = self.height + 2 * pad as usize;
+        let points = (0..height as isize)
+            .flat_map(|y| (0..width as isize).map(move |x| (x, y)));
+
+        let rows = points
+            .clone()
+            .map(|(x, y)| {
+                let (x, y) = (left + x, top + y);
+                reduce(grow, (-n..=n).map(|d| self.contains(x + d, y)))
+            })
+            .collect::<Vec<_>>();
+        let in_rows = |x: isize, y: isize| {
+            (0..height as isize).contains(&y)
+                && rows[x as usize + y as usize * width]
+        };
+        let mask = points
+            .map(|(x, y)| reduce(grow, (-n..=n).map(|d| in_rows(x, y + d))))
+            .collect();
+
+        Self::from_mask((left, top), (width, height), mask)
+    }
+
+    /// Transforms the mask around the center of its bounds.
+    pub fn transform(&self, transform: Transform) -> Self {
+        let size = (self.width, self.height);
+        let (width, height) = transform.size(size);
+        let origin = (
+            self.origin.0 + (self.width as isize - width as isize) / 2,
+            self.origin.1 + (self.height as isize - height as isize) / 2,
+        );
+
+        let mask = transform.apply(size, &self.mask);
+
+        Self::from_mask(origin, (width, height), mask)
+    }
+
+    pub fn scale(&self, scaler: Scaler) -> Self {
+        let size = (self.width, self.height);
+        let mask = scaler.apply(size, &self.mask);
+
+        Self::from_mask(self.origin, scaler.size(size), mask)
+    }
+
+    fn combine<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(bool, bool) -> bool,
+    {
+        let left = self.origin.0.min(other.origin.0);
+        let top = self.origin.1.min(other.origin.1);
+        let right = (self.origin.0 + self.width as isize)
+            .max(other.origin.0 + other.width as isize);
+        let bottom = (self.origin.1 + self.height as isize)
+            .max(other.origin.1 + other.height as isize);
+        let (width, height) =
+            ((right - left) as usize, (bottom - top) as usize);
+
+        let mask = (top..bottom)
+            .flat_map(|y| (left..right).map(move |x| (x, y)))
+            .map(|(x, y)| f(self.contains(x, y), other.contains(x, y)))
+            .collect();
+
+        Self::from_mask((left, top), (width, height), mask)
+    }
+
+    fn to_inner(&self, x: isize, y: isize) -> Option<usize> {
+        let x = x - self.origin.0;
+        let y = y - self.origin.1;
+
+        if x < 0
+            || y < 0
+            || x as usize >= self.width
+            || y as usize >= self.height
+        {
+            None
+        } else {
+            Some(x as usize + y as usize * self.width)
+        }
+    }
+}
+
+fn reduce<I>(any: bool, mut values: I) -> bool
+where
+    I: Iterator<Item = bool>,
+{
+    if any {
+        values.any(|value| value)
+    } else {
+        values.all(|value| value)
+    }
+}
+
+#[derive(Clone)]
+pub struct Clip {
+    selection: Selection,
+    pixels: Vec<Color>,
+}
+
+impl Clip {
+    pub fn new<F>(selection: Selection, get_pixel: F) -> Self
+    where
+        F: Fn(isize, isize) -> Color,
+    {
+        let (left, top) = selection.origin;
+        let pixels = (0..selection.height)
+            .flat_map(|y| (0..selection.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (x, y) = (left + x as isize, top + y as isize);
+                if selection.contains(x, y) {
+                    get_pixel(x, y)
+                } else {
+                    Color::transparent()
+                }
+            })
+            .collect();
+
+        Self { selection, pixels }
+    }
+
+    pub fn selection(&self) -> &Selection {
+        &self.selection
+    }
+
+    pub fn origin(&self) -> (isize, isize) {
+        self.selection.origin
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        self.selection.size()
+    }
+
+    pub fn pixel(&self, x: isize, y: isize) -> Option<Color> {
+        self.selection
+            .to_inner(x, y)
+            .filter(|ix| self.selection.mask[*ix])
+            .map(|ix| self.pixels[ix])
+    }
+
+    pub fn translate(&mut self, delta: (isize, isize)) {
+        self.selection.translate(delta);
+    }
+
+    pub fn transform(&self, transform: Transform) -> Self {
+        let size = (self.selection.width, self.selection.height);
+
+        Self {
+            selection: self.selection.transform(transform),
+            pixels: transform.apply(size, &self.pixels),
+        }
+    }
+
+    pub fn scale(&self, scaler: Scaler) -> Self {
+        let size = (self.selection.width, self.selection.height);
+
+        Self {
+            selection: self.selection.scale(scaler),
+            pixels: scaler.apply(size, &self.pixels),
+        }
+    }
+}
+
+pub struct Ants<'s> {
+    pub selection: &'s Selection,
+    pub phase: usize,
+}
+
+impl Widget for Ants<'_> {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        let viewport = match buffer.viewport() {
+            Some(viewport) => viewport,
+            None => return,
+        };
+        let zoom = viewport.zoom() as isize;
+        let phase = self.phase as isize;
+
+        let mut put_ant = |x: isize, y: isize| {
+            if x < 0 || y < 0 {
+                return;
+            }
+
+            let color = if ((x + y) / ANT_LENGTH + phase) % 2 == 0 {
+                Color::black()
+            } else {
+                Color::white()
+            };
+            buffer.put_frame_pixel(x as usize, y as usize, color);
+        };
+
+        let (range_x, range_y) = viewport.visible();
+        let points = range_y
+            .flat_map(|y| range_x.clone().map(move |x| (x, y)))
+            .map(|(x, y)| (x as isize, y as isize))
+            .filter(|(x, y)| self.selection.contains(*x, *y));
+
+        for (x, y) in points {
+            let (frame_x, frame_y) = viewport.canvas_to_frame((x, y));
+            let (right, bottom) = (frame_x + zoom - 1, frame_y + zoom - 1);
+
+            for i in 0..zoom {
+                if !self.selection.contains(x - 1, y) {
+                    put_ant(frame_x, frame_y + i);
+                }
+                if !self.selection.contains(x + 1, y) {
+                    put_ant(right, frame_y + i);
+                }
+                if !self.selection.contains(x, y - 1) {
+                    put_ant(frame_x + i, frame_y);
+                }
+                if !self.selection.contains(x, y + 1) {
+                    put_ant(frame_x + i, bottom);
+                }
+            }
+        }
+    }
+}
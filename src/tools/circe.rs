@@ -1,6 +1,13 @@
-use crate::{buffer::GuardedBuffer, canvas::Canvas, color::Color, widget::Widget};
+use crate::{
+    brush::Brush,
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    pattern::Pattern,
+    widget::Widget,
+};
 
-use super::Tool;
+use super::{FillMode, Tool};
 
 use std::f32::consts::FRAC_1_SQRT_2;
 
@@ -9,6 +16,10 @@ pub struct Circe {
     radius: f32,
     down: bool,
     outline_color: Color,
+    fill_color: Color,
+    fill_mode: FillMode,
+    pattern: Pattern,
+    brush: Brush,
 }
 
 impl Circe {
@@ -18,8 +29,50 @@ impl Circe {
             radius: 0.0,
             down: false,
             outline_color: Color::black(),
+            fill_color: Color::white(),
+            fill_mode: FillMode::Outline,
+            pattern: Pattern::default(),
+            brush: Brush::default(),
         }
     }
+
+    fn outline(&self) -> Vec<(isize, isize)> {
+        let radius = self.radius.abs();
+        let max_x = (FRAC_1_SQRT_2 * radius).ceil() as isize;
+        let mut points = vec![];
+
+        for pixel_x in 0..=max_x {
+            let pixel_y = (radius
+                * ((1.0 - (pixel_x as f32 / radius).powf(2.0)).sqrt()))
+            .round() as isize;
+
+            for (m_x, m_y) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+                points.push((
+                    self.origin.0 + (m_x * pixel_x),
+                    self.origin.1 + (m_y * pixel_y),
+                ));
+                points.push((
+                    self.origin.0 + (m_x * pixel_y),
+                    self.origin.1 + (m_y * pixel_x),
+                ));
+            }
+        }
+
+        points
+    }
+
+    fn draw<F>(&self, put_pixel: F)
+    where
+        F: FnMut(usize, usize, Color),
+    {
+        super::draw_shape(
+            self.outline(),
+            (self.fill_mode, &self.pattern),
+            &self.brush,
+            (self.outline_color, self.fill_color),
+            put_pixel,
+        );
+    }
 }
 
 impl Widget for Circe {
@@ -28,25 +81,7 @@ impl Widget for Circe {
             return;
         }
 
-        let max_x = (FRAC_1_SQRT_2 * self.radius).ceil() as isize;
-
-        for pixel_x in 0..=max_x {
-            let pixel_y = (self.radius * ((1.0 - (pixel_x as f32 / self.radius).powf(2.0)).sqrt()))
-                .round() as isize;
-
-            for (m_x, m_y) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
-                buffer.put_pixel(
-                    (self.origin.0 + (m_x * pixel_x)) as usize,
-                    (self.origin.1 + (m_y * pixel_y)) as usize,
-                    self.outline_color,
-                );
-                buffer.put_pixel(
-                    (self.origin.0 + (m_x * pixel_y)) as usize,
-                    (self.origin.1 + (m_y * pixel_x)) as usize,
-                    self.outline_color,
-                );
-            }
-        }
+        self.draw(|x, y, color| buffer.put_pixel(x, y, color));
     }
 }
 
@@ -80,29 +115,30 @@ impl Tool for Circe {
         }
         self.down = false;
 
-        let max_x = (FRAC_1_SQRT_2 * self.radius.abs()).ceil() as isize;
-
-        for pixel_x in 0..=max_x {
-            let pixel_y = (self.radius.abs()
-                * ((1.0 - (pixel_x as f32 / self.radius.abs()).powf(2.0)).sqrt()))
-            .round() as isize;
-
-            for (m_x, m_y) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
-                canvas.set_pixel(
-                    (self.origin.0 + (m_x * pixel_x)) as usize,
-                    (self.origin.1 + (m_y * pixel_y)) as usize,
-                    self.outline_color,
-                );
-                canvas.set_pixel(
-                    (self.origin.0 + (m_x * pixel_y)) as usize,
-                    (self.origin.1 + (m_y * pixel_x)) as usize,
-                    self.outline_color,
-                );
-            }
-        }
+        self.draw(|x, y, color| canvas.set_pixel(x, y, color));
     }
 
     fn set_outline_color(&mut self, outline_color: Color) {
         self.outline_color = outline_color;
     }
+
+    fn set_fill_color(&mut self, fill_color: Color) {
+        self.fill_color = fill_color;
+    }
+
+    fn set_fill_mode(&mut self, fill_mode: FillMode) {
+        self.fill_mode = fill_mode;
+    }
+
+    fn set_pattern(&mut self, pattern: Pattern) {
+        self.pattern = pattern;
+    }
+
+    fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush;
+    }
+
+    fn uses_brush(&self) -> bool {
+        true
+    }
 }
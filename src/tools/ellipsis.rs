@@ -0,0 +1,236 @@
+use crate::{
+    brush::Brush,
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    pattern::Pattern,
+    widget::Widget,
+};
+
+use super::{FillMode, Modifiers, Tool};
+
+pub struct Ellipsis {
+    origin: (isize, isize),
+    mouse: (isize, isize),
+    down: bool,
+    outline_color: Color,
+    fill_color: Color,
+    fill_mode: FillMode,
+    pattern: Pattern,
+    brush: Brush,
+    modifiers: Modifiers,
+}
+
+impl Ellipsis {
+    pub fn new() -> Self {
+        Self {
+            origin: (0, 0),
+            mouse: (0, 0),
+            down: false,
+            outline_color: Color::black(),
+            fill_color: Color::white(),
+            fill_mode: FillMode::Outline,
+            pattern: Pattern::default(),
+            brush: Brush::default(),
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn bounds(&self) -> ((isize, isize), (isize, isize)) {
+        let mouse = if self.modifiers.shift {
+            super::constrain_square(self.origin, self.mouse)
+        } else {
+            self.mouse
+        };
+
+        if self.modifiers.ctrl || self.modifiers.alt {
+            super::center_on(self.origin, mouse)
+        } else {
+            (self.origin, mouse)
+        }
+    }
+
+    fn outline(&self) -> Vec<(isize, isize)> {
+        let ((x0, y0), (x1, y1)) = self.bounds();
+        let mut points = plot_ellipse(
+            (x0.min(x1) as i64, y0.min(y1) as i64),
+            (x0.max(x1) as i64, y0.max(y1) as i64),
+        );
+        points.sort_unstable();
+        points.dedup();
+
+        points
+    }
+
+    fn draw<F>(&self, put_pixel: F)
+    where
+        F: FnMut(usize, usize, Color),
+    {
+        super::draw_shape(
+            self.outline(),
+            (self.fill_mode, &self.pattern),
+            &self.brush,
+            (self.outline_color, self.fill_color),
+            put_pixel,
+        );
+    }
+}
+
+// Midpoint ellipse inscribed in the rectangle, using only integer
+// arithmetic so that even-sized boxes come out symmetric.
+fn plot_ellipse(
+    (mut x0, mut y0): (i64, i64),
+    (mut x1, mut y1): (i64, i64),
+) -> Vec<(isize, isize)> {
+    let mut points = vec![];
+    let mut put = |x: i64, y: i64| points.push((x as isize, y as isize));
+
+    let a = x1 - x0;
+    let b = y1 - y0;
+    let b1 = b & 1;
+    let mut d_x = 4 * (1 - a) * b * b;
+    let mut d_y = 4 * (b1 + 1) * a * a;
+    let mut err = d_x + d_y + b1 * a * a;
+    let step_x = 8 * b * b;
+    let step_y = 8 * a * a;
+
+    y0 += (b + 1) / 2;
+    y1 = y0 - b1;
+
+    loop {
+        put(x1, y0);
+        put(x0, y0);
+        put(x0, y1);
+        put(x1, y1);
+
+        let e2 = 2 * err;
+        if e2 <= d_y {
+            y0 += 1;
+            y1 -= 1;
+            d_y += step_y;
+            err += d_y;
+        }
+        if e2 >= d_x || 2 * err > d_y {
+            x0 += 1;
+            x1 -= 1;
+            d_x += step_x;
+            err += d_x;
+        }
+
+        if x0 > x1 {
+            break;
+        }
+    }
+
+    while y0 - y1 <= b {
+        put(x0 - 1, y0);
+        put(x1 + 1, y0);
+        put(x0 - 1, y1);
+        put(x1 + 1, y1);
+        y0 += 1;
+        y1 -= 1;
+    }
+
+    points
+}
+
+impl Widget for Ellipsis {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        if !self.down {
+            return;
+        }
+
+        self.draw(|x, y, color| buffer.put_pixel(x, y, color));
+    }
+}
+
+impl Tool for Ellipsis {
+    fn handle_press(&mut self, mouse: (isize, isize), _canvas: &mut Canvas) {
+        self.down = true;
+        self.origin = mouse;
+        self.mouse = mouse;
+    }
+
+    fn handle_hold(
+        &mut self,
+        _prev_mouse: (isize, isize),
+        curr_mouse: (isize, isize),
+        _canvas: &mut Canvas,
+    ) {
+        self.mouse = curr_mouse;
+    }
+
+    fn handle_release(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        self.mouse = mouse;
+        self.down = false;
+
+        self.draw(|x, y, color| canvas.set_pixel(x, y, color));
+    }
+
+    fn set_outline_color(&mut self, outline_color: Color) {
+        self.outline_color = outline_color;
+    }
+
+    fn set_fill_color(&mut self, fill_color: Color) {
+        self.fill_color = fill_color;
+    }
+
+    fn set_fill_mode(&mut self, fill_mode: FillMode) {
+        self.fill_mode = fill_mode;
+    }
+
+    fn set_pattern(&mut self, pattern: Pattern) {
+        self.pattern = pattern;
+    }
+
+    fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush;
+    }
+
+    fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    fn uses_brush(&self) -> bool {
+        true
+    }
+
+    fn uses_alt(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plot_ellipse_reaches_bounds() {
+        // Tall narrow boxes used to stop one row short at the top and the
+        // bottom.
+        for width in 1..=40 {
+            for height in 1..=40 {
+                let (right, bottom) = (width - 1, height - 1);
+                let points =
+                    plot_ellipse((0, 0), (right as i64, bottom as i64));
+                let xs = points.iter().map(|(x, _)| *x);
+                let ys = points.iter().map(|(_, y)| *y);
+
+                assert_eq!(
+                    (xs.clone().min(), xs.max()),
+                    (Some(0), Some(right)),
+                    "{}x{}",
+                    width,
+                    height
+                );
+                assert_eq!(
+                    (ys.clone().min(), ys.max()),
+                    (Some(0), Some(bottom)),
+                    "{}x{}",
+                    width,
+                    height
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{
+    brush::Brush,
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    widget::Widget,
+};
+
+use super::Tool;
+
+pub struct Erazor {
+    brush: Brush,
+}
+
+impl Erazor {
+    pub fn new() -> Self {
+        Self {
+            brush: Brush::default(),
+        }
+    }
+}
+
+impl Widget for Erazor {
+    fn display(&self, _buffer: &mut GuardedBuffer<'_, '_>) {}
+}
+
+impl Tool for Erazor {
+    fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        self.brush.stamp(mouse, |x, y| canvas.clear_pixel(x, y));
+    }
+
+    fn handle_hold(
+        &mut self,
+        prev_mouse: (isize, isize),
+        curr_mouse: (isize, isize),
+        canvas: &mut Canvas,
+    ) {
+        super::plot_line(prev_mouse, curr_mouse)
+            .into_iter()
+            .for_each(|point| {
+                self.brush.stamp(point, |x, y| canvas.clear_pixel(x, y))
+            });
+    }
+
+    fn handle_release(&mut self, _mouse: (isize, isize), _canvas: &mut Canvas) {
+    }
+
+    fn set_outline_color(&mut self, _outline_color: Color) {}
+
+    fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush;
+    }
+
+    fn uses_brush(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    dither::Dithering,
+    region::{self, RegionOptions},
+    widget::Widget,
+};
+
+use super::Tool;
+
+/// Gradient from the outline color at the press point to the secondary
+/// color at the release point. It fills the selection or, without one, the
+/// region `Phill` would fill.
+pub struct Grady {
+    origin: (isize, isize),
+    mouse: (isize, isize),
+    down: bool,
+    radial: bool,
+    outline_color: Color,
+    secondary_color: Color,
+    dithering: Dithering,
+    palette: Vec<Color>,
+    options: RegionOptions,
+}
+
+impl Grady {
+    pub fn linear() -> Self {
+        Self {
+            origin: (0, 0),
+            mouse: (0, 0),
+            down: false,
+            radial: false,
+            outline_color: Color::black(),
+            secondary_color: Color::white(),
+            dithering: Dithering::Bayer,
+            palette: vec![],
+            options: RegionOptions::default(),
+        }
+    }
+
+    pub fn radial() -> Self {
+        Self {
+            radial: true,
+            ..Self::linear()
+        }
+    }
+
+    fn color_at(&self, (x, y): (isize, isize)) -> Color {
+        let (d_x, d_y) = (
+            (self.mouse.0 - self.origin.0) as f32,
+            (self.mouse.1 - self.origin.1) as f32,
+        );
+        let (p_x, p_y) =
+            ((x - self.origin.0) as f32, (y - self.origin.1) as f32);
+        let length = d_x * d_x + d_y * d_y;
+
+        let t = if length == 0.0 {
+            0.0
+        } else if self.radial {
+            ((p_x * p_x + p_y * p_y) / length).sqrt()
+        } else {
+            (p_x * d_x + p_y * d_y) / length
+        };
+
+        self.outline_color.lerp(self.secondary_color, t)
+    }
+}
+
+impl Widget for Grady {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        if !self.down {
+            return;
+        }
+
+        for (x, y) in super::plot_line(self.origin, self.mouse) {
+            if x >= 0 && y >= 0 {
+                buffer.put_pixel(x as usize, y as usize, self.color_at((x, y)));
+            }
+        }
+    }
+}
+
+impl Tool for Grady {
+    fn handle_press(&mut self, mouse: (isize, isize), _canvas: &mut Canvas) {
+        self.down = true;
+        self.origin = mouse;
+        self.mouse = mouse;
+    }
+
+    fn handle_hold(
+        &mut self,
+        _prev_mouse: (isize, isize),
+        curr_mouse: (isize, isize),
+        _canvas: &mut Canvas,
+    ) {
+        self.mouse = curr_mouse;
+    }
+
+    fn handle_release(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        self.mouse = mouse;
+        self.down = false;
+
+        let region = match canvas.selection() {
+            Some(selection) => selection.clone(),
+            None => {
+                match region::find_region(canvas, self.origin, self.options) {
+                    Some(region) => region,
+                    None => return,
+                }
+            }
+        };
+
+        for (x, y) in region.points() {
+            if x < 0 || y < 0 {
+                continue;
+            }
+
+            let (x, y) = (x as usize, y as usize);
+            let color = self.color_at((x as isize, y as isize));
+            let color = self.dithering.apply(color, &self.palette, (x, y));
+            canvas.set_pixel(x, y, color);
+        }
+    }
+
+    fn set_outline_color(&mut self, outline_color: Color) {
+        self.outline_color = outline_color;
+    }
+
+    fn set_secondary_color(&mut self, secondary_color: Color) {
+        self.secondary_color = secondary_color;
+    }
+
+    fn set_region_options(&mut self, options: RegionOptions) {
+        self.options = options;
+    }
+
+    fn set_dithering(&mut self, dithering: Dithering, palette: &[Color]) {
+        self.dithering = dithering;
+        self.palette = palette.to_vec();
+    }
+}
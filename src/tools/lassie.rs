@@ -0,0 +1,171 @@
+use crate::{
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    selection::Selection,
+    widget::Widget,
+};
+
+use super::{Modifiers, Tool};
+
+const DASH_LENGTH: usize = 2;
+
+/// Lasso selection. Freehand mode follows the drag and closes the path on
+/// release, polygon mode adds a vertex per click and closes the path when
+/// the first or the last vertex is clicked again.
+pub struct Lassie {
+    vertices: Vec<(isize, isize)>,
+    polygon: bool,
+    modifiers: Modifiers,
+}
+
+impl Lassie {
+    pub fn new() -> Self {
+        Self {
+            vertices: vec![],
+            polygon: false,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    pub fn polygon() -> Self {
+        Self {
+            polygon: true,
+            ..Self::new()
+        }
+    }
+
+    fn path(&self) -> Vec<(isize, isize)> {
+        let mut path = self
+            .vertices
+            .windows(2)
+            .flat_map(|edge| super::plot_line(edge[0], edge[1]))
+            .collect::<Vec<_>>();
+        path.extend(self.vertices.first());
+
+        path
+    }
+
+    fn close(&mut self, canvas: &mut Canvas) {
+        let vertices = std::mem::take(&mut self.vertices);
+
+        if vertices.len() < 2 {
+            canvas.set_selection(None);
+        } else {
+            let selection = rasterize(&vertices);
+            super::apply_selection(canvas, selection, self.modifiers);
+        }
+    }
+}
+
+/// Scanline fill of the closed polygon, sampled at pixel centers with the
+/// even-odd rule. The edges are added on top so thin paths stay selected.
+fn rasterize(vertices: &[(isize, isize)]) -> Selection {
+    let xs = vertices.iter().map(|(x, _)| *x);
+    let ys = vertices.iter().map(|(_, y)| *y);
+    let (left, right) = (xs.clone().min().unwrap(), xs.max().unwrap());
+    let (top, bottom) = (ys.clone().min().unwrap(), ys.max().unwrap());
+    let width = (right - left + 1) as usize;
+    let height = (bottom - top + 1) as usize;
+    let mut mask = vec![false; width * height];
+    let mut select = |x: isize, y: isize| {
+        mask[(x - left) as usize + (y - top) as usize * width] = true;
+    };
+
+    let edges = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(start, end)| (*start, *end));
+
+    for y in top..=bottom {
+        let mut crossings = edges
+            .clone()
+            .filter(|((_, y0), (_, y1))| (*y0 <= y) != (*y1 <= y))
+            .map(|((x0, y0), (x1, y1))| {
+                let t = (y - y0) as f32 / (y1 - y0) as f32;
+                x0 as f32 + t * (x1 - x0) as f32
+            })
+            .collect::<Vec<_>>();
+        crossings.sort_by(|a, b| a.total_cmp(b));
+
+        for span in crossings.chunks_exact(2) {
+            let start = span[0].ceil() as isize;
+            let end = span[1].floor() as isize;
+            for x in start..=end {
+                select(x, y);
+            }
+        }
+    }
+
+    for (start, end) in edges {
+        for (x, y) in super::plot_line(start, end) {
+            select(x, y);
+        }
+    }
+
+    Selection::from_mask((left, top), (width, height), mask)
+}
+
+impl Widget for Lassie {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        for (i, (x, y)) in self.path().into_iter().enumerate() {
+            if x < 0 || y < 0 {
+                continue;
+            }
+
+            let color = if (i / DASH_LENGTH).is_multiple_of(2) {
+                Color::black()
+            } else {
+                Color::white()
+            };
+            buffer.put_pixel(x as usize, y as usize, color);
+        }
+    }
+}
+
+impl Tool for Lassie {
+    fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        canvas.commit_floating();
+
+        let closing = self.polygon
+            && (self.vertices.first() == Some(&mouse)
+                || self.vertices.last() == Some(&mouse));
+
+        if closing {
+            self.close(canvas);
+        } else {
+            self.vertices.push(mouse);
+        }
+    }
+
+    fn handle_hold(
+        &mut self,
+        _prev_mouse: (isize, isize),
+        curr_mouse: (isize, isize),
+        _canvas: &mut Canvas,
+    ) {
+        if self.polygon {
+            if let Some(vertex) = self.vertices.last_mut() {
+                *vertex = curr_mouse;
+            }
+        } else if self.vertices.last() != Some(&curr_mouse) {
+            self.vertices.push(curr_mouse);
+        }
+    }
+
+    fn handle_release(&mut self, _mouse: (isize, isize), canvas: &mut Canvas) {
+        if !self.polygon {
+            self.close(canvas);
+        }
+    }
+
+    fn set_outline_color(&mut self, _outline_color: Color) {}
+
+    fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    fn uses_alt(&self) -> bool {
+        true
+    }
+}
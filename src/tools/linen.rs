@@ -1,17 +1,20 @@
 use crate::{
+    brush::Brush,
     buffer::GuardedBuffer,
     canvas::Canvas,
     color::Color,
     widget::Widget,
 };
 
-use super::Tool;
+use super::{Modifiers, Tool};
 
 pub struct Linen {
     origin: (isize, isize),
     mouse: (isize, isize),
     down: bool,
     outline_color: Color,
+    brush: Brush,
+    modifiers: Modifiers,
 }
 
 impl Linen {
@@ -21,8 +24,25 @@ impl Linen {
             mouse: (0, 0),
             down: false,
             outline_color: Color::black(),
+            brush: Brush::default(),
+            modifiers: Modifiers::default(),
         }
     }
+
+    fn line(&self) -> Vec<(isize, isize)> {
+        let mouse = if self.modifiers.shift {
+            super::constrain_angle(self.origin, self.mouse)
+        } else {
+            self.mouse
+        };
+        let (start, end) = if self.modifiers.ctrl {
+            super::center_on(self.origin, mouse)
+        } else {
+            (self.origin, mouse)
+        };
+
+        super::plot_line(start, end)
+    }
 }
 
 impl Widget for Linen {
@@ -31,10 +51,12 @@ impl Widget for Linen {
             return;
         }
 
-        super::plot_line(self.origin, self.mouse)
+        self.line()
             .into_iter()
-            .for_each(|(x, y)| {
-                buffer.put_pixel(x, y, Color::new(0xff, 0xff, 0x00))
+            .for_each(|point| {
+                self.brush.stamp(point, |x, y| {
+                    buffer.put_pixel(x, y, Color::new(0xff, 0xff, 0x00))
+                })
             });
     }
 }
@@ -63,12 +85,28 @@ impl Tool for Linen {
         self.mouse = mouse;
         self.down = false;
 
-        super::plot_line(self.origin, self.mouse)
+        self.line()
             .into_iter()
-            .for_each(|(x, y)| canvas.set_pixel(x, y, self.outline_color));
+            .for_each(|point| {
+                self.brush.stamp(point, |x, y| {
+                    canvas.set_pixel(x, y, self.outline_color)
+                })
+            });
     }
 
     fn set_outline_color(&mut self, outline_color: Color) {
         self.outline_color = outline_color;
     }
+
+    fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush;
+    }
+
+    fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    fn uses_brush(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,124 @@
+use crate::{
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    selection::{Ants, Selection},
+    widget::Widget,
+};
+
+use super::{Modifiers, Tool};
+
+enum Action {
+    Select,
+    Move,
+}
+
+pub struct Markee {
+    origin: (isize, isize),
+    mouse: (isize, isize),
+    action: Option<Action>,
+    modifiers: Modifiers,
+}
+
+impl Markee {
+    pub fn new() -> Self {
+        Self {
+            origin: (0, 0),
+            mouse: (0, 0),
+            action: None,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn selection(&self) -> Selection {
+        let mouse = if self.modifiers.shift {
+            super::constrain_square(self.origin, self.mouse)
+        } else {
+            self.mouse
+        };
+        let (start, end) = if self.modifiers.ctrl {
+            super::center_on(self.origin, mouse)
+        } else {
+            (self.origin, mouse)
+        };
+
+        Selection::rect(start, end)
+    }
+}
+
+impl Widget for Markee {
+    fn display(&self, buffer: &mut GuardedBuffer<'_, '_>) {
+        if let Some(Action::Select) = self.action {
+            let selection = self.selection();
+            let ants = Ants {
+                selection: &selection,
+                phase: 0,
+            };
+            ants.display(buffer);
+        }
+    }
+}
+
+impl Tool for Markee {
+    fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        self.origin = mouse;
+        self.mouse = mouse;
+
+        let (x, y) = mouse;
+        if canvas
+            .floating()
+            .is_some_and(|clip| clip.pixel(x, y).is_some())
+        {
+            self.action = Some(Action::Move);
+            return;
+        }
+
+        canvas.commit_floating();
+        let in_selection = canvas
+            .selection()
+            .is_some_and(|selection| selection.contains(x, y));
+
+        self.action = if !in_selection {
+            Some(Action::Select)
+        } else if canvas.lift_selection() {
+            Some(Action::Move)
+        } else {
+            None
+        };
+    }
+
+    fn handle_hold(
+        &mut self,
+        prev_mouse: (isize, isize),
+        curr_mouse: (isize, isize),
+        canvas: &mut Canvas,
+    ) {
+        self.mouse = curr_mouse;
+
+        if let Some(Action::Move) = self.action {
+            canvas.move_floating((
+                curr_mouse.0 - prev_mouse.0,
+                curr_mouse.1 - prev_mouse.1,
+            ));
+        }
+    }
+
+    fn handle_release(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        self.mouse = mouse;
+
+        if let Some(Action::Select) = self.action.take() {
+            let selection = if self.origin == self.mouse {
+                None
+            } else {
+                Some(self.selection())
+            };
+            canvas.set_selection(selection);
+        }
+    }
+
+    fn set_outline_color(&mut self, _outline_color: Color) {}
+
+    fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+}
@@ -1,16 +1,41 @@
-use crate::{canvas::Canvas, color::Color, widget::Widget};
+use crate::{
+    brush::Brush,
+    canvas::Canvas,
+    color::Color,
+    dither::Dithering,
+    pattern::Pattern,
+    region::RegionOptions,
+    selection::Selection,
+    widget::Widget,
+};
 
 pub mod circe;
+pub mod ellipsis;
+pub mod erazor;
+pub mod grady;
+pub mod lassie;
 pub mod linen;
+pub mod markee;
 pub mod penicilin;
 pub mod phill;
+pub mod pipette;
 pub mod rectangel;
+pub mod wanda;
 
 pub use circe::Circe;
+pub use ellipsis::Ellipsis;
+pub use erazor::Erazor;
+pub use grady::Grady;
+pub use lassie::Lassie;
 pub use linen::Linen;
+pub use markee::Markee;
 pub use penicilin::Penicilin;
 pub use phill::Phill;
+pub use pipette::Pipette;
 pub use rectangel::Rectangel;
+pub use wanda::Wanda;
+
+use std::collections::BTreeMap;
 
 pub trait Tool: Widget {
     fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas);
@@ -22,17 +47,182 @@ pub trait Tool: Widget {
     );
     fn handle_release(&mut self, mouse: (isize, isize), canvas: &mut Canvas);
     fn set_outline_color(&mut self, color: Color);
+
+    fn set_fill_color(&mut self, _color: Color) {}
+
+    /// The color the outline color is not: the secondary one, or the
+    /// primary one during a right-button stroke.
+    fn set_secondary_color(&mut self, _color: Color) {}
+
+    fn set_fill_mode(&mut self, _fill_mode: FillMode) {}
+
+    fn set_pattern(&mut self, _pattern: Pattern) {}
+
+    fn set_brush(&mut self, _brush: Brush) {}
+
+    fn set_modifiers(&mut self, _modifiers: Modifiers) {}
+
+    fn set_region_options(&mut self, _options: RegionOptions) {}
+
+    fn set_dithering(&mut self, _dithering: Dithering, _palette: &[Color]) {}
+
+    fn uses_brush(&self) -> bool {
+        false
+    }
+
+    /// Tools that read Alt themselves are not swapped for the `Pipette`
+    /// while it is held.
+    fn uses_alt(&self) -> bool {
+        false
+    }
+
+    fn take_picked_color(&mut self) -> Option<Color> {
+        None
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FillMode {
+    Outline,
+    Fill,
+    OutlineAndFill,
+}
+
+impl FillMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Outline => Self::Fill,
+            Self::Fill => Self::OutlineAndFill,
+            Self::OutlineAndFill => Self::Outline,
+        }
+    }
+
+    pub fn outline(self) -> bool {
+        self != Self::Fill
+    }
+
+    pub fn fill(self) -> bool {
+        self != Self::Outline
+    }
+}
+
+fn constrain_square(
+    origin: (isize, isize),
+    mouse: (isize, isize),
+) -> (isize, isize) {
+    let d_x = mouse.0 - origin.0;
+    let d_y = mouse.1 - origin.1;
+    let d = d_x.abs().max(d_y.abs());
+
+    (
+        origin.0 + if d_x < 0 { -d } else { d },
+        origin.1 + if d_y < 0 { -d } else { d },
+    )
+}
+
+fn constrain_angle(
+    origin: (isize, isize),
+    mouse: (isize, isize),
+) -> (isize, isize) {
+    let d_x = mouse.0 - origin.0;
+    let d_y = mouse.1 - origin.1;
+
+    if 5 * d_y.abs() <= 2 * d_x.abs() {
+        (mouse.0, origin.1)
+    } else if 5 * d_x.abs() <= 2 * d_y.abs() {
+        (origin.0, mouse.1)
+    } else {
+        constrain_square(origin, mouse)
+    }
+}
+
+fn center_on(
+    origin: (isize, isize),
+    mouse: (isize, isize),
+) -> ((isize, isize), (isize, isize)) {
+    let d_x = mouse.0 - origin.0;
+    let d_y = mouse.1 - origin.1;
+
+    ((origin.0 - d_x, origin.1 - d_y), mouse)
+}
+
+/// Replaces the canvas selection, or adds to it with Shift and subtracts
+/// from it with Alt.
+fn apply_selection(
+    canvas: &mut Canvas,
+    selection: Selection,
+    modifiers: Modifiers,
+) {
+    let selection = match canvas.selection() {
+        Some(current) if modifiers.shift => current.union(&selection),
+        Some(current) if modifiers.alt => current.subtract(&selection),
+        _ if modifiers.alt => return,
+        _ => selection,
+    };
+
+    canvas.set_selection(Some(selection).filter(|s| !s.is_empty()));
+}
+
+fn draw_shape<F>(
+    outline: Vec<(isize, isize)>,
+    (fill_mode, pattern): (FillMode, &Pattern),
+    brush: &Brush,
+    (outline_color, fill_color): (Color, Color),
+    mut put_pixel: F,
+) where
+    F: FnMut(usize, usize, Color),
+{
+    if fill_mode.fill() {
+        for (x, y) in fill_spans(&outline) {
+            if x < 0 || y < 0 {
+                continue;
+            }
+
+            let (x, y) = (x as usize, y as usize);
+            if let Some(color) = pattern.paint(fill_color, (x, y)) {
+                put_pixel(x, y, color);
+            }
+        }
+    }
+
+    if fill_mode.outline() {
+        for point in outline {
+            brush.stamp(point, |x, y| put_pixel(x, y, outline_color));
+        }
+    }
+}
+
+fn fill_spans(outline: &[(isize, isize)]) -> Vec<(isize, isize)> {
+    let mut spans = BTreeMap::new();
+
+    for &(x, y) in outline {
+        let (min_x, max_x) = spans.entry(y).or_insert((x, x));
+        *min_x = x.min(*min_x);
+        *max_x = x.max(*max_x);
+    }
+
+    spans
+        .into_iter()
+        .flat_map(|(y, (min_x, max_x))| (min_x..=max_x).map(move |x| (x, y)))
+        .collect()
 }
 
 fn plot_line(
     (prev_x, prev_y): (isize, isize),
     (curr_x, curr_y): (isize, isize),
-) -> Vec<(usize, usize)> {
+) -> Vec<(isize, isize)> {
     let d_x = curr_x - prev_x;
     let d_y = curr_y - prev_y;
 
     if d_x == 0 && d_y == 0 {
-        vec![(curr_x as usize, curr_y as usize)]
+        vec![(curr_x, curr_y)]
     } else if d_x.abs() > d_y.abs() || d_y == 0 {
         let min_x = curr_x.min(prev_x);
         let max_x = curr_x.max(prev_x);
@@ -43,7 +233,7 @@ fn plot_line(
                 let pixel_y = prev_y as f32 + m * (pixel_x - prev_x) as f32;
                 let pixel_y = pixel_y.round() as isize;
 
-                (pixel_x as usize, pixel_y as usize)
+                (pixel_x, pixel_y)
             })
             .collect()
     } else {
@@ -56,7 +246,7 @@ fn plot_line(
                 let pixel_x = prev_x as f32 + m * (pixel_y - prev_y) as f32;
                 let pixel_x = pixel_x.round() as isize;
 
-                (pixel_x as usize, pixel_y as usize)
+                (pixel_x, pixel_y)
             })
             .collect()
     }
@@ -1,4 +1,5 @@
 use crate::{
+    brush::Brush,
     buffer::GuardedBuffer,
     canvas::Canvas,
     color::Color,
@@ -9,12 +10,14 @@ use super::Tool;
 
 pub struct Penicilin {
     outline_color: Color,
+    brush: Brush,
 }
 
 impl Penicilin {
     pub fn new() -> Self {
         Self {
             outline_color: Color::black(),
+            brush: Brush::default(),
         }
     }
 }
@@ -24,16 +27,9 @@ impl Widget for Penicilin {
 }
 
 impl Tool for Penicilin {
-    fn handle_press(
-        &mut self,
-        (mouse_x, mouse_y): (isize, isize),
-        canvas: &mut Canvas,
-    ) {
-        canvas.set_pixel(
-            mouse_x as usize,
-            mouse_y as usize,
-            self.outline_color,
-        );
+    fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        self.brush
+            .stamp(mouse, |x, y| canvas.set_pixel(x, y, self.outline_color));
     }
 
     fn handle_hold(
@@ -44,7 +40,11 @@ impl Tool for Penicilin {
     ) {
         super::plot_line(prev_mouse, curr_mouse)
             .into_iter()
-            .for_each(|(x, y)| canvas.set_pixel(x, y, self.outline_color));
+            .for_each(|point| {
+                self.brush.stamp(point, |x, y| {
+                    canvas.set_pixel(x, y, self.outline_color)
+                })
+            });
     }
 
     fn handle_release(&mut self, _mouse: (isize, isize), _canvas: &mut Canvas) {
@@ -53,4 +53,12 @@ impl Tool for Penicilin {
     fn set_outline_color(&mut self, outline_color: Color) {
         self.outline_color = outline_color;
     }
+
+    fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush;
+    }
+
+    fn uses_brush(&self) -> bool {
+        true
+    }
 }
@@ -2,21 +2,25 @@ use crate::{
     buffer::GuardedBuffer,
     canvas::Canvas,
     color::Color,
+    pattern::Pattern,
+    region::{self, RegionOptions},
     widget::Widget,
 };
 
 use super::Tool;
 
-use std::collections::HashSet;
-
 pub struct Phill {
     outline_color: Color,
+    pattern: Pattern,
+    options: RegionOptions,
 }
 
 impl Phill {
     pub fn new() -> Self {
         Self {
             outline_color: Color::black(),
+            pattern: Pattern::default(),
+            options: RegionOptions::default(),
         }
     }
 }
@@ -26,40 +30,17 @@ impl Widget for Phill {
 }
 
 impl Tool for Phill {
-    fn handle_press(&mut self, (x, y): (isize, isize), canvas: &mut Canvas) {
-        let mut checked = HashSet::new();
-        let mut queue = vec![(x, y)];
-        let color = match canvas.get_pixel(x as usize, y as usize) {
-            Some(color) => color,
-            _ => return,
-        };
-        let neighbours = |x: isize, y: isize| {
-            [
-                (x, y + 1),
-                (x, (y - 1).max(0)),
-                (x + 1, y),
-                ((x - 1).max(0), y),
-            ]
+    fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        let region = match region::find_region(canvas, mouse, self.options) {
+            Some(region) => region,
+            None => return,
         };
 
-        while let Some((x, y)) = queue.pop() {
-            checked.insert((x, y));
-
-            match canvas.get_pixel(x as usize, y as usize) {
-                Some(pixel) if pixel == color => {
-                    canvas.set_pixel(
-                        x as usize,
-                        y as usize,
-                        self.outline_color,
-                    );
-
-                    queue.extend(
-                        neighbours(x, y)
-                            .iter()
-                            .filter(|coords| !checked.contains(coords)),
-                    );
-                }
-                _ => {}
+        for (x, y) in region.points() {
+            let (x, y) = (x as usize, y as usize);
+            let color = self.pattern.paint(self.outline_color, (x, y));
+            if let Some(color) = color {
+                canvas.set_pixel(x, y, color);
             }
         }
     }
@@ -78,4 +59,12 @@ impl Tool for Phill {
     fn set_outline_color(&mut self, outline_color: Color) {
         self.outline_color = outline_color;
     }
+
+    fn set_pattern(&mut self, pattern: Pattern) {
+        self.pattern = pattern;
+    }
+
+    fn set_region_options(&mut self, options: RegionOptions) {
+        self.options = options;
+    }
 }
@@ -0,0 +1,57 @@
+use crate::{
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    widget::Widget,
+};
+
+use super::Tool;
+
+pub struct Pipette {
+    picked_color: Option<Color>,
+}
+
+impl Pipette {
+    pub fn new() -> Self {
+        Self { picked_color: None }
+    }
+
+    fn pick(&mut self, (mouse_x, mouse_y): (isize, isize), canvas: &Canvas) {
+        if mouse_x < 0 || mouse_y < 0 {
+            return;
+        }
+
+        let color = canvas.get_pixel(mouse_x as usize, mouse_y as usize);
+        if color.is_some() {
+            self.picked_color = color;
+        }
+    }
+}
+
+impl Widget for Pipette {
+    fn display(&self, _buffer: &mut GuardedBuffer<'_, '_>) {}
+}
+
+impl Tool for Pipette {
+    fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        self.pick(mouse, canvas);
+    }
+
+    fn handle_hold(
+        &mut self,
+        _prev_mouse: (isize, isize),
+        curr_mouse: (isize, isize),
+        canvas: &mut Canvas,
+    ) {
+        self.pick(curr_mouse, canvas);
+    }
+
+    fn handle_release(&mut self, _mouse: (isize, isize), _canvas: &mut Canvas) {
+    }
+
+    fn set_outline_color(&mut self, _outline_color: Color) {}
+
+    fn take_picked_color(&mut self) -> Option<Color> {
+        self.picked_color.take()
+    }
+}
@@ -1,18 +1,25 @@
 use crate::{
+    brush::Brush,
     buffer::GuardedBuffer,
     canvas::Canvas,
     color::Color,
+    pattern::Pattern,
     utils,
     widget::Widget,
 };
 
-use super::Tool;
+use super::{FillMode, Modifiers, Tool};
 
 pub struct Rectangel {
     origin: (isize, isize),
     mouse: (isize, isize),
     down: bool,
     outline_color: Color,
+    fill_color: Color,
+    fill_mode: FillMode,
+    pattern: Pattern,
+    brush: Brush,
+    modifiers: Modifiers,
 }
 
 impl Rectangel {
@@ -22,8 +29,57 @@ impl Rectangel {
             mouse: (0, 0),
             down: false,
             outline_color: Color::black(),
+            fill_color: Color::white(),
+            fill_mode: FillMode::Outline,
+            pattern: Pattern::default(),
+            brush: Brush::default(),
+            modifiers: Modifiers::default(),
         }
     }
+
+    fn bounds(&self) -> ((isize, isize), (isize, isize)) {
+        let mouse = if self.modifiers.shift {
+            super::constrain_square(self.origin, self.mouse)
+        } else {
+            self.mouse
+        };
+
+        if self.modifiers.ctrl {
+            super::center_on(self.origin, mouse)
+        } else {
+            (self.origin, mouse)
+        }
+    }
+
+    fn outline(&self) -> Vec<(isize, isize)> {
+        let (start, end) = self.bounds();
+        let mut points = vec![];
+
+        for x in utils::range_inclusive(start.0, end.0) {
+            points.push((x, start.1));
+            points.push((x, end.1));
+        }
+
+        for y in utils::range_inclusive(start.1, end.1) {
+            points.push((start.0, y));
+            points.push((end.0, y));
+        }
+
+        points
+    }
+
+    fn draw<F>(&self, put_pixel: F)
+    where
+        F: FnMut(usize, usize, Color),
+    {
+        super::draw_shape(
+            self.outline(),
+            (self.fill_mode, &self.pattern),
+            &self.brush,
+            (self.outline_color, self.fill_color),
+            put_pixel,
+        );
+    }
 }
 
 impl Widget for Rectangel {
@@ -32,31 +88,7 @@ impl Widget for Rectangel {
             return;
         }
 
-        for x in utils::range_inclusive(self.origin.0, self.mouse.0) {
-            buffer.put_pixel(
-                x as usize,
-                self.origin.1 as usize,
-                self.outline_color,
-            );
-            buffer.put_pixel(
-                x as usize,
-                self.mouse.1 as usize,
-                self.outline_color,
-            );
-        }
-
-        for y in utils::range_inclusive(self.origin.1, self.mouse.1) {
-            buffer.put_pixel(
-                self.origin.0 as usize,
-                y as usize,
-                self.outline_color,
-            );
-            buffer.put_pixel(
-                self.mouse.0 as usize,
-                y as usize,
-                self.outline_color,
-            );
-        }
+        self.draw(|x, y, color| buffer.put_pixel(x, y, color));
     }
 }
 
@@ -84,34 +116,34 @@ impl Tool for Rectangel {
         self.mouse = mouse;
         self.down = false;
 
-        for x in utils::range_inclusive(self.origin.0, self.mouse.0) {
-            canvas.set_pixel(
-                x as usize,
-                self.origin.1 as usize,
-                self.outline_color,
-            );
-            canvas.set_pixel(
-                x as usize,
-                self.mouse.1 as usize,
-                self.outline_color,
-            );
-        }
-
-        for y in utils::range_inclusive(self.origin.1, self.mouse.1) {
-            canvas.set_pixel(
-                self.origin.0 as usize,
-                y as usize,
-                self.outline_color,
-            );
-            canvas.set_pixel(
-                self.mouse.0 as usize,
-                y as usize,
-                self.outline_color,
-            );
-        }
+        self.draw(|x, y, color| canvas.set_pixel(x, y, color));
     }
 
     fn set_outline_color(&mut self, outline_color: Color) {
         self.outline_color = outline_color;
     }
+
+    fn set_fill_color(&mut self, fill_color: Color) {
+        self.fill_color = fill_color;
+    }
+
+    fn set_fill_mode(&mut self, fill_mode: FillMode) {
+        self.fill_mode = fill_mode;
+    }
+
+    fn set_pattern(&mut self, pattern: Pattern) {
+        self.pattern = pattern;
+    }
+
+    fn set_brush(&mut self, brush: Brush) {
+        self.brush = brush;
+    }
+
+    fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    fn uses_brush(&self) -> bool {
+        true
+    }
 }
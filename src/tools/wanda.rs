@@ -0,0 +1,62 @@
+use crate::{
+    buffer::GuardedBuffer,
+    canvas::Canvas,
+    color::Color,
+    region::{self, RegionOptions},
+    widget::Widget,
+};
+
+use super::{Modifiers, Tool};
+
+pub struct Wanda {
+    options: RegionOptions,
+    modifiers: Modifiers,
+}
+
+impl Wanda {
+    pub fn new() -> Self {
+        Self {
+            options: RegionOptions::default(),
+            modifiers: Modifiers::default(),
+        }
+    }
+}
+
+impl Widget for Wanda {
+    fn display(&self, _buffer: &mut GuardedBuffer<'_, '_>) {}
+}
+
+impl Tool for Wanda {
+    fn handle_press(&mut self, mouse: (isize, isize), canvas: &mut Canvas) {
+        canvas.commit_floating();
+
+        if let Some(region) = region::find_region(canvas, mouse, self.options) {
+            super::apply_selection(canvas, region, self.modifiers);
+        }
+    }
+
+    fn handle_hold(
+        &mut self,
+        _prev_mouse: (isize, isize),
+        _curr_mouse: (isize, isize),
+        _canvas: &mut Canvas,
+    ) {
+    }
+
+    fn handle_release(&mut self, _mouse: (isize, isize), _canvas: &mut Canvas) {
+    }
+
+    fn set_outline_color(&mut self, _outline_color: Color) {}
+
+    fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    fn set_region_options(&mut self, options: RegionOptions) {
+        self.options = options;
+    }
+
+    fn uses_alt(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,43 @@
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Transform {
+    FlipHorizontal,
+    FlipVertical,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+}
+
+impl Transform {
+    pub fn size(self, (width, height): (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::RotateClockwise | Self::RotateCounterClockwise => {
+                (height, width)
+            }
+            _ => (width, height),
+        }
+    }
+
+    pub fn apply<T: Copy>(
+        self,
+        (width, height): (usize, usize),
+        pixels: &[T],
+    ) -> Vec<T> {
+        let (new_width, _) = self.size((width, height));
+        let mut transformed = pixels.to_vec();
+
+        for y in 0..height {
+            for x in 0..width {
+                let (new_x, new_y) = match self {
+                    Self::FlipHorizontal => (width - 1 - x, y),
+                    Self::FlipVertical => (x, height - 1 - y),
+                    Self::RotateClockwise => (height - 1 - y, x),
+                    Self::RotateCounterClockwise => (y, width - 1 - x),
+                    Self::Rotate180 => (width - 1 - x, height - 1 - y),
+                };
+                transformed[new_x + new_y * new_width] = pixels[x + y * width];
+            }
+        }
+
+        transformed
+    }
+}
@@ -1,17 +1,4 @@
-use std::{
-    cmp::Ord,
-    ops::{Range, RangeInclusive},
-};
-
-pub fn range<T>(a: T, b: T) -> Range<T>
-where
-    T: Ord + Copy,
-{
-    let min = a.min(b);
-    let max = a.max(b);
-
-    min..max
-}
+use std::{cmp::Ord, ops::RangeInclusive};
 
 pub fn range_inclusive<T>(a: T, b: T) -> RangeInclusive<T>
 where
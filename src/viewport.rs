@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+const MAX_VIEW_WIDTH: usize = 320;
+const MAX_VIEW_HEIGHT: usize = 240;
+const ZOOM_LEVELS: [usize; 6] = [1, 2, 4, 8, 16, 32];
+
+#[derive(Copy, Clone)]
+pub struct Viewport {
+    origin: (usize, usize),
+    size: (usize, usize),
+    canvas_size: (usize, usize),
+    scroll: (isize, isize),
+    zoom: usize,
+}
+
+impl Viewport {
+    pub fn new(origin: (usize, usize), canvas_size: (usize, usize)) -> Self {
+        let mut viewport = Self {
+            origin,
+            size: (0, 0),
+            canvas_size: (0, 0),
+            scroll: (0, 0),
+            zoom: 1,
+        };
+        viewport.set_canvas_size(canvas_size);
+
+        viewport
+    }
+
+    pub fn origin(&self) -> (usize, usize) {
+        self.origin
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    pub fn zoom(&self) -> usize {
+        self.zoom
+    }
+
+    pub fn set_canvas_size(&mut self, (width, height): (usize, usize)) {
+        self.canvas_size = (width, height);
+        self.size = (width.min(MAX_VIEW_WIDTH), height.min(MAX_VIEW_HEIGHT));
+        self.clamp_scroll();
+    }
+
+    pub fn frame_to_canvas(&self, (x, y): (isize, isize)) -> (isize, isize) {
+        let x = x - self.origin.0 as isize + self.scroll.0;
+        let y = y - self.origin.1 as isize + self.scroll.1;
+        let zoom = self.zoom as isize;
+
+        (x.div_euclid(zoom), y.div_euclid(zoom))
+    }
+
+    pub fn canvas_to_frame(&self, (x, y): (isize, isize)) -> (isize, isize) {
+        let zoom = self.zoom as isize;
+
+        (
+            x * zoom - self.scroll.0 + self.origin.0 as isize,
+            y * zoom - self.scroll.1 + self.origin.1 as isize,
+        )
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        let ((left, top), (width, height)) = (self.origin, self.size);
+
+        (left..(left + width)).contains(&x)
+            && (top..(top + height)).contains(&y)
+    }
+
+    pub fn visible(&self) -> (Range<usize>, Range<usize>) {
+        let ((left, top), (width, height)) = (self.origin, self.size);
+        let (min_x, min_y) =
+            self.frame_to_canvas((left as isize, top as isize));
+        let (max_x, max_y) = self.frame_to_canvas((
+            (left + width) as isize - 1,
+            (top + height) as isize - 1,
+        ));
+        let (canvas_width, canvas_height) = self.canvas_size;
+        let clamp = |v: isize, max: usize| v.clamp(0, max as isize) as usize;
+
+        (
+            clamp(min_x, canvas_width)..clamp(max_x + 1, canvas_width),
+            clamp(min_y, canvas_height)..clamp(max_y + 1, canvas_height),
+        )
+    }
+
+    pub fn pan(&mut self, (d_x, d_y): (isize, isize)) {
+        self.scroll.0 -= d_x;
+        self.scroll.1 -= d_y;
+        self.clamp_scroll();
+    }
+
+    pub fn zoom_in(&mut self, anchor: (isize, isize)) {
+        let zoom = ZOOM_LEVELS.iter().find(|zoom| **zoom > self.zoom);
+        if let Some(zoom) = zoom {
+            self.set_zoom(*zoom, anchor);
+        }
+    }
+
+    pub fn zoom_out(&mut self, anchor: (isize, isize)) {
+        let zoom = ZOOM_LEVELS.iter().rev().find(|zoom| **zoom < self.zoom);
+        if let Some(zoom) = zoom {
+            self.set_zoom(*zoom, anchor);
+        }
+    }
+
+    fn set_zoom(&mut self, zoom: usize, (x, y): (isize, isize)) {
+        let x = x - self.origin.0 as isize;
+        let y = y - self.origin.1 as isize;
+        let (old, new) = (self.zoom as isize, zoom as isize);
+        let rescale = |scroll: isize, v: isize| (scroll + v) * new / old - v;
+
+        self.scroll = (rescale(self.scroll.0, x), rescale(self.scroll.1, y));
+        self.zoom = zoom;
+        self.clamp_scroll();
+    }
+
+    fn clamp_scroll(&mut self) {
+        let max_x = self.canvas_size.0 * self.zoom - self.size.0;
+        let max_y = self.canvas_size.1 * self.zoom - self.size.1;
+
+        self.scroll.0 = self.scroll.0.clamp(0, max_x as isize);
+        self.scroll.1 = self.scroll.1.clamp(0, max_y as isize);
+    }
+}